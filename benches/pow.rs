@@ -0,0 +1,77 @@
+//! Whole-exponent `**` against plain `powf`, for speed and for accuracy.
+//!
+//! Run with `cargo bench --bench pow`. Squaring is only worth it where it's also no less
+//! accurate, which is what `MAX_SQUARED_EXP` in element.rs is set from: the f32 error columns
+//! show repeated squaring drifting from the correctly rounded result as the exponent grows,
+//! while `powf` stays within an ulp.
+
+use rustpython_ndarray::element::Element;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1 << 16;
+const ROUNDS: usize = 200;
+
+fn main() {
+    let f64s: Vec<f64> = (0..LEN).map(|i| 0.5 + i as f64 / LEN as f64).collect();
+    let f32s: Vec<f32> = f64s.iter().map(|&x| x as f32).collect();
+
+    println!("exp   py_pow      powf        squaring    | f32 ulps: squaring  powf");
+    for exp in [-1, 2, 3, 8, 32] {
+        let py_pow = time(|| f64s.iter().map(|&x| x.py_pow(exp as f64)).sum::<f64>());
+        let powf = time(|| f64s.iter().map(|&x| x.powf(exp as f64)).sum::<f64>());
+        let squaring = time(|| f64s.iter().map(|&x| by_squaring(x, exp)).sum::<f64>());
+
+        println!(
+            "{exp:<5} {:<11} {:<11} {:<11} | {:>18}  {:>4}",
+            format!("{py_pow:.2?}"),
+            format!("{powf:.2?}"),
+            format!("{squaring:.2?}"),
+            max_ulps(&f32s, |x| by_squaring(x, exp), exp),
+            max_ulps(&f32s, |x| x.powf(exp as f32), exp),
+        );
+    }
+}
+
+/// Mean time of one pass of `f` over the inputs
+fn time(f: impl Fn() -> f64) -> Duration {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    start.elapsed() / ROUNDS as u32
+}
+
+/// The worst error of `f` in f32, in ulps of the f64 result rounded to f32
+fn max_ulps(inputs: &[f32], f: impl Fn(f32) -> f32, exp: i32) -> u32 {
+    inputs
+        .iter()
+        .map(|&x| {
+            let exact = (x as f64).powi(exp) as f32;
+            f(x).to_bits().abs_diff(exact.to_bits())
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Exponentiation by squaring at any exponent, as `**` did before it was limited to
+/// `MAX_SQUARED_EXP`
+fn by_squaring<T: num_traits::Float>(base: T, exp: i32) -> T {
+    let mut result = T::one();
+    let mut square = base;
+    let mut remaining = exp.unsigned_abs();
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = result * square;
+        }
+        square = square * square;
+        remaining >>= 1;
+    }
+
+    if exp < 0 {
+        T::one() / result
+    } else {
+        result
+    }
+}
@@ -0,0 +1,73 @@
+/// What `cov` and `corrcoef` do with NaNs in the observations
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Let them spread, as numpy does: every entry involving a variable with a NaN is NaN
+    Propagate,
+    /// Drop each observation where any variable is NaN, keeping the complete ones
+    Omit,
+    /// Refuse them
+    Raise,
+}
+
+impl NanPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "propagate" => Some(Self::Propagate),
+            "omit" => Some(Self::Omit),
+            "raise" => Some(Self::Raise),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps only the observations where no variable is NaN. Each variable is a row of
+/// observations, all the same length.
+pub fn complete_observations(variables: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = variables.first().map_or(0, Vec::len);
+    let complete: Vec<usize> = (0..n)
+        .filter(|&i| variables.iter().all(|variable| !variable[i].is_nan()))
+        .collect();
+    variables
+        .iter()
+        .map(|variable| complete.iter().map(|&i| variable[i]).collect())
+        .collect()
+}
+
+/// The covariance of each pair of `variables`, each a row of observations, dividing by
+/// `n - ddof` for `n` observations
+pub fn cov(variables: &[Vec<f64>], ddof: f64) -> Vec<Vec<f64>> {
+    let n = variables.first().map_or(0, Vec::len);
+    let centred: Vec<Vec<f64>> = variables
+        .iter()
+        .map(|variable| {
+            let mean = variable.iter().sum::<f64>() / n as f64;
+            variable.iter().map(|x| x - mean).collect()
+        })
+        .collect();
+
+    let divisor = n as f64 - ddof;
+    centred
+        .iter()
+        .map(|a| {
+            centred
+                .iter()
+                .map(|b| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>() / divisor)
+                .collect()
+        })
+        .collect()
+}
+
+/// Pearson correlation coefficients from the covariance matrix `cov`, clipped to `[-1, 1]`
+/// against rounding, as in numpy
+pub fn corrcoef(cov: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let stddevs: Vec<f64> = cov.iter().enumerate().map(|(i, row)| row[i].sqrt()).collect();
+    cov.iter()
+        .zip(&stddevs)
+        .map(|(row, a)| {
+            row.iter()
+                .zip(&stddevs)
+                .map(|(c, b)| (c / (a * b)).clamp(-1.0, 1.0))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,403 @@
+use num_traits::Float;
+use std::cmp::Ordering;
+
+/// Elementwise operations whose semantics depend on the dtype
+pub trait Element: Copy + PartialOrd {
+    /// Floor division and remainder, following Python's sign rules: the quotient rounds towards
+    /// negative infinity and the remainder takes the sign of the divisor
+    fn py_divmod(self, other: Self) -> (Self, Self);
+
+    /// Addition, wrapping around on overflow for integers
+    fn py_add(self, other: Self) -> Self;
+
+    /// Subtraction, wrapping around on overflow for integers
+    fn py_sub(self, other: Self) -> Self;
+
+    /// Multiplication, wrapping around on overflow for integers
+    fn py_mul(self, other: Self) -> Self;
+
+    fn py_floordiv(self, other: Self) -> Self {
+        self.py_divmod(other).0
+    }
+
+    fn py_mod(self, other: Self) -> Self {
+        self.py_divmod(other).1
+    }
+
+    fn py_pow(self, exp: Self) -> Self;
+
+    /// Rounds half to even at `decimals` places after the point, or before it if negative
+    fn py_round(self, decimals: i32) -> Self;
+
+    fn is_nan(self) -> bool;
+
+    /// -1, 0 or 1 by the sign. NaN stays NaN, unlike with `signum`.
+    fn py_sign(self) -> Self;
+
+    /// Truthiness, as Python sees it. NaN is truthy.
+    fn is_nonzero(self) -> bool;
+
+    /// Total order for sorting, putting NaNs last as numpy does
+    fn sort_cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other)
+            .unwrap_or_else(|| self.is_nan().cmp(&other.is_nan()))
+    }
+}
+
+impl Element for f32 {
+    fn py_divmod(self, other: Self) -> (Self, Self) {
+        float_divmod(self, other)
+    }
+
+    fn py_add(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn py_sub(self, other: Self) -> Self {
+        self - other
+    }
+
+    fn py_mul(self, other: Self) -> Self {
+        self * other
+    }
+
+    fn py_pow(self, exp: Self) -> Self {
+        float_pow(self, exp)
+    }
+
+    fn py_round(self, decimals: i32) -> Self {
+        float_round(self, decimals, f32::round_ties_even)
+    }
+
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+
+    fn is_nonzero(self) -> bool {
+        self != 0.0
+    }
+
+    fn py_sign(self) -> Self {
+        float_sign(self)
+    }
+}
+
+impl Element for f64 {
+    fn py_divmod(self, other: Self) -> (Self, Self) {
+        float_divmod(self, other)
+    }
+
+    fn py_add(self, other: Self) -> Self {
+        self + other
+    }
+
+    fn py_sub(self, other: Self) -> Self {
+        self - other
+    }
+
+    fn py_mul(self, other: Self) -> Self {
+        self * other
+    }
+
+    fn py_pow(self, exp: Self) -> Self {
+        float_pow(self, exp)
+    }
+
+    fn py_round(self, decimals: i32) -> Self {
+        float_round(self, decimals, f64::round_ties_even)
+    }
+
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn is_nonzero(self) -> bool {
+        self != 0.0
+    }
+
+    fn py_sign(self) -> Self {
+        float_sign(self)
+    }
+}
+
+/// Integers as numpy treats them: arithmetic wraps, and division by zero gives zero rather than
+/// raising
+macro_rules! impl_int_element {
+    ($int:ident) => {
+        impl Element for $int {
+            fn py_divmod(self, other: Self) -> (Self, Self) {
+                if other == 0 {
+                    return (0, 0);
+                }
+
+                let mut div = self.wrapping_div(other);
+                let mut rem = self.wrapping_rem(other);
+                if rem != 0 && (rem < 0) != (other < 0) {
+                    div = div.wrapping_sub(1);
+                    rem += other;
+                }
+                (div, rem)
+            }
+
+            fn py_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn py_sub(self, other: Self) -> Self {
+                self.wrapping_sub(other)
+            }
+
+            fn py_mul(self, other: Self) -> Self {
+                self.wrapping_mul(other)
+            }
+
+            /// Negative exponents have no integer result, so this truncates towards zero. The
+            /// array functions raise before getting here, as numpy does.
+            fn py_pow(self, exp: Self) -> Self {
+                match u32::try_from(exp) {
+                    Ok(exp) => self.wrapping_pow(exp),
+                    Err(_) if exp < 0 => match self {
+                        1 => 1,
+                        -1 if exp % 2 == 0 => 1,
+                        -1 => -1,
+                        _ => 0,
+                    },
+                    // Past what wrapping_pow takes, so squaring by hand, wrapping as it goes
+                    Err(_) => {
+                        let mut exp = exp as u64;
+                        let (mut base, mut out): (Self, Self) = (self, 1);
+                        while exp > 0 {
+                            if exp & 1 == 1 {
+                                out = out.wrapping_mul(base);
+                            }
+                            base = base.wrapping_mul(base);
+                            exp >>= 1;
+                        }
+                        out
+                    }
+                }
+            }
+
+            fn py_round(self, decimals: i32) -> Self {
+                if decimals >= 0 {
+                    return self;
+                }
+
+                // Rounds half to even at a multiple of the scale; past the range of the type,
+                // everything rounds to zero
+                let ten: $int = 10;
+                let Some(scale) = ten.checked_pow(decimals.unsigned_abs()) else {
+                    return 0;
+                };
+                let (div, rem) = self.py_divmod(scale);
+                // Comparing rem with what's left of the scale, as doubling it could overflow
+                let round_up = match rem.cmp(&(scale - rem)) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => div % 2 != 0,
+                    Ordering::Less => false,
+                };
+                let div = if round_up { div.wrapping_add(1) } else { div };
+                div.wrapping_mul(scale)
+            }
+
+            fn is_nan(self) -> bool {
+                false
+            }
+
+            fn is_nonzero(self) -> bool {
+                self != 0
+            }
+
+            fn py_sign(self) -> Self {
+                self.signum()
+            }
+        }
+    };
+}
+
+impl_int_element!(i32);
+impl_int_element!(i64);
+
+/// Elements that can be read straight from another dtype's bytes, as `view(dtype=...)` does
+///
+/// # Safety
+///
+/// Every bit pattern of the type's size must be a valid value, with no padding. `bool` isn't
+/// plain, as only 0 and 1 are valid.
+pub unsafe trait Plain: Copy + Send + Sync + std::fmt::Debug + 'static {}
+
+unsafe impl Plain for f32 {}
+unsafe impl Plain for f64 {}
+unsafe impl Plain for i32 {}
+unsafe impl Plain for i64 {}
+
+/// Formats an element the way Python's repr does, for printing arrays
+pub trait PyRepr {
+    fn py_repr(&self) -> String;
+
+    /// The repr with at most `precision` places after the point, which only changes floats
+    fn py_repr_rounded(&self, _precision: usize) -> String {
+        self.py_repr()
+    }
+}
+
+macro_rules! impl_py_repr_by_debug {
+    ($($t:ty),*) => {
+        $(
+            impl PyRepr for $t {
+                fn py_repr(&self) -> String {
+                    // Debug keeps the trailing `.0` on whole floats, like Python does
+                    format!("{self:?}")
+                }
+            }
+        )*
+    };
+}
+
+impl_py_repr_by_debug!(i32, i64);
+
+macro_rules! impl_py_repr_for_float {
+    ($($t:ty),*) => {
+        $(
+            impl PyRepr for $t {
+                fn py_repr(&self) -> String {
+                    // Debug keeps the trailing `.0` on whole floats, like Python does
+                    format!("{self:?}")
+                }
+
+                fn py_repr_rounded(&self, precision: usize) -> String {
+                    trim_fixed_point(format!("{self:.precision$}"))
+                }
+            }
+        )*
+    };
+}
+
+impl_py_repr_for_float!(f32, f64);
+
+impl PyRepr for bool {
+    fn py_repr(&self) -> String {
+        if *self { "True" } else { "False" }.to_string()
+    }
+}
+
+/// numpy's closeness test, `|a - b| <= atol + rtol * |b|`. Infinities are only close to
+/// themselves, and NaNs only to each other with `equal_nan`.
+pub fn is_close(a: f64, b: f64, rtol: f64, atol: f64, equal_nan: bool) -> bool {
+    if a == b {
+        return true;
+    }
+    if a.is_nan() || b.is_nan() {
+        return equal_nan && a.is_nan() && b.is_nan();
+    }
+    if a.is_infinite() || b.is_infinite() {
+        return false;
+    }
+    (a - b).abs() <= atol + rtol * b.abs()
+}
+
+/// Drops the trailing zeros of a float formatted with a fixed number of places, as numpy does,
+/// but keeps one place so that it still reads as a float: `1.50` becomes `1.5`, and `2` or
+/// `2.00` becomes `2.0`
+fn trim_fixed_point(mut formatted: String) -> String {
+    if formatted.contains('.') {
+        while formatted.ends_with('0') && !formatted.ends_with(".0") {
+            formatted.pop();
+        }
+    } else if formatted.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        // Zero places, but inf and NaN have none to keep
+        formatted.push_str(".0");
+    }
+    formatted
+}
+
+fn float_sign<T: Float>(x: T) -> T {
+    if x > T::zero() {
+        T::one()
+    } else if x < T::zero() {
+        -T::one()
+    } else {
+        // Zero, or NaN
+        x
+    }
+}
+
+/// Largest whole exponent, by magnitude, that `float_pow` multiplies out rather than passing
+/// to `powf`. Squaring is faster at any exponent, but past this it's also less accurate than
+/// `powf`, as `cargo bench --bench pow` shows.
+const MAX_SQUARED_EXP: i32 = 2;
+
+/// Raises `base` to `exp`. Small whole exponents (as in the very common `a ** 2`) are done by
+/// multiplication, which rounds no more than twice. Anything else goes to `powf`, as the
+/// rounding error of repeated squaring grows with the exponent.
+fn float_pow<T: Float>(base: T, exp: T) -> T {
+    match exp.to_i32() {
+        Some(whole) if whole.abs() <= MAX_SQUARED_EXP && T::from(whole) == Some(exp) => {
+            powi_by_squaring(base, whole)
+        }
+        _ => base.powf(exp),
+    }
+}
+
+fn powi_by_squaring<T: Float>(base: T, exp: i32) -> T {
+    let mut result = T::one();
+    let mut square = base;
+    let mut remaining = exp.unsigned_abs();
+
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = result * square;
+        }
+        square = square * square;
+        remaining >>= 1;
+    }
+
+    if exp < 0 {
+        T::one() / result
+    } else {
+        result
+    }
+}
+
+/// numpy's rounding: scale by a power of ten, round to the nearest whole number, then undo
+fn float_round<T: Float>(x: T, decimals: i32, round_ties_even: fn(T) -> T) -> T {
+    let scale = T::from(10).expect("Float from 10").powi(decimals.abs());
+    if decimals >= 0 {
+        round_ties_even(x * scale) / scale
+    } else {
+        round_ties_even(x / scale) * scale
+    }
+}
+
+/// Python's float divmod, as numpy implements it
+fn float_divmod<T: Float>(a: T, b: T) -> (T, T) {
+    let mut rem = a % b;
+
+    // Division by zero gives inf or nan, and the remainder is nan
+    if b.is_zero() {
+        return (a / b, rem);
+    }
+
+    let mut div = (a - rem) / b;
+    if rem.is_zero() {
+        rem = T::zero().copysign(b);
+    } else if (b < T::zero()) != (rem < T::zero()) {
+        rem = rem + b;
+        div = div - T::one();
+    }
+
+    let floordiv = if div.is_zero() {
+        T::zero().copysign(a / b)
+    } else {
+        // div is already very nearly whole, but may be a hair under after rounding
+        let half = T::one() / (T::one() + T::one());
+        let floor = div.floor();
+        if div - floor > half {
+            floor + T::one()
+        } else {
+            floor
+        }
+    };
+
+    (floordiv, rem)
+}
@@ -1,118 +1,1501 @@
-use ndarray::{ArrayViewD, ArrayViewMutD, IxDyn, SliceInfo, SliceInfoElem};
+use ndarray::{
+    Array2, ArrayD, ArrayView1, ArrayView2, ArrayViewD, ArrayViewMutD, Axis, Dimension, Ix2,
+    IxDyn, LinalgScalar, SliceInfo, SliceInfoElem, Zip,
+};
+use num_traits::{Float, FromPrimitive};
 use rustpython_vm::{
-    builtins::{PyInt, PyNone, PySlice, PyTuple},
+    builtins::{PyInt, PyList, PyNone, PySlice, PyStr, PyTuple},
     convert::ToPyObject,
     PyObject, PyObjectRef, PyResult, TryFromObject, VirtualMachine,
 };
 
 use std::{
     fmt::Display,
-    sync::{Arc, RwLock},
+    sync::{Arc, LazyLock, Mutex, RwLock},
 };
 
-use crate::GenericArray;
+use crate::{
+    element::{Element, Plain, PyRepr},
+    AnyArray, GenericArray,
+};
 
 pub type DynamicSlice = SliceInfo<Vec<SliceInfoElem>, IxDyn, IxDyn>;
 
+/// Memory layout of a copy, as in numpy's `order=` argument
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Order {
+    /// Row-major
+    C,
+    /// Column-major
+    F,
+    /// Whichever of the two the source already uses
+    Keep,
+}
+
+impl Order {
+    pub fn from_pyobject(obj: &PyObject) -> Option<Self> {
+        match obj.downcast_ref::<PyStr>()?.as_str() {
+            "C" => Some(Self::C),
+            "F" => Some(Self::F),
+            "A" | "K" => Some(Self::Keep),
+            _ => None,
+        }
+    }
+}
+
+/// Copies `view` into a new array laid out in memory according to `order`
+pub fn to_owned_with_order<T: Clone>(view: ArrayViewD<'_, T>, order: Order) -> ArrayD<T> {
+    let fortran = match order {
+        Order::C => false,
+        Order::F => true,
+        Order::Keep => !view.is_standard_layout() && view.t().is_standard_layout(),
+    };
+
+    if fortran {
+        // A row-major copy of the transpose is the column-major layout of the original
+        view.t().as_standard_layout().into_owned().reversed_axes()
+    } else {
+        view.as_standard_layout().into_owned()
+    }
+}
+
+/// Whether `view` is already laid out in memory according to `order`, so that a copy in that
+/// order would be no different
+pub fn is_in_order<T>(view: &ArrayViewD<'_, T>, order: Order) -> bool {
+    match order {
+        Order::C => view.is_standard_layout(),
+        Order::F => view.t().is_standard_layout(),
+        Order::Keep => true,
+    }
+}
+
 /// Provides a sliced representation of an array, where the slices are deferred until needed.
 #[derive(Debug, Clone)]
 pub struct SlicedArcArray<T> {
     slices: Vec<DynamicSlice>,
-    unsliced: Arc<RwLock<ndarray::ArrayD<T>>>,
+    /// Axis order of a transposed view, applied after the slices: axis `i` of the view is axis
+    /// `permutation[i]` of the sliced array
+    permutation: Option<Vec<usize>>,
+    unsliced: Storage<T>,
+}
+
+/// The whole of the data an array views
+#[derive(Debug)]
+enum Storage<T> {
+    /// Elements of our own dtype
+    Owned(Arc<RwLock<ndarray::ArrayD<T>>>),
+    /// Another dtype's elements, with their bits read as ours, as `view(dtype=...)` gives
+    Reinterpreted(Arc<dyn ForeignStorage<T>>),
+}
+
+// Derived, this would only clone storage of Clone elements
+impl<T> Clone for Storage<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Storage::Owned(arr) => Storage::Owned(arr.clone()),
+            Storage::Reinterpreted(foreign) => Storage::Reinterpreted(foreign.clone()),
+        }
+    }
+}
+
+impl<T> Storage<T> {
+    fn read(&self, readfn: &mut dyn FnMut(ArrayViewD<'_, T>)) {
+        match self {
+            Storage::Owned(arr) => readfn(arr.read().unwrap().view()),
+            Storage::Reinterpreted(foreign) => foreign.read(readfn),
+        }
+    }
+
+    fn write(&self, writefn: &mut dyn FnMut(ArrayViewMutD<'_, T>)) {
+        match self {
+            Storage::Owned(arr) => writefn(arr.write().unwrap().view_mut()),
+            Storage::Reinterpreted(foreign) => foreign.write(writefn),
+        }
+    }
+
+    /// Identifies the data underneath any reinterpretation, so that two arrays sharing it can
+    /// tell, whatever their dtypes
+    fn root(&self) -> *const () {
+        match self {
+            Storage::Owned(arr) => Arc::as_ptr(arr).cast(),
+            Storage::Reinterpreted(foreign) => foreign.root(),
+        }
+    }
+}
+
+/// Storage of another dtype, seen as elements of `T`
+trait ForeignStorage<T>: std::fmt::Debug + Send + Sync {
+    fn read(&self, readfn: &mut dyn FnMut(ArrayViewD<'_, T>));
+    fn write(&self, writefn: &mut dyn FnMut(ArrayViewMutD<'_, T>));
+    fn root(&self) -> *const ();
+}
+
+/// Storage of `S` elements, read bit for bit as some other `Plain` type of the same size
+#[derive(Debug)]
+struct Reinterpreted<S> {
+    source: Storage<S>,
+}
+
+impl<S: Plain, T: Plain> ForeignStorage<T> for Reinterpreted<S> {
+    fn read(&self, readfn: &mut dyn FnMut(ArrayViewD<'_, T>)) {
+        self.source.read(&mut |source| readfn(reinterpret(source)))
+    }
+
+    fn write(&self, writefn: &mut dyn FnMut(ArrayViewMutD<'_, T>)) {
+        self.source.write(&mut |source| writefn(reinterpret_mut(source)))
+    }
+
+    fn root(&self) -> *const () {
+        self.source.root()
+    }
+}
+
+/// Panics unless `S` and `T` can be read as each other, which `Plain` leaves to the size and
+/// alignment
+fn assert_same_layout<S, T>() {
+    assert_eq!(std::mem::size_of::<S>(), std::mem::size_of::<T>());
+    assert_eq!(std::mem::align_of::<S>(), std::mem::align_of::<T>());
+}
+
+/// `view` with the bits of each element read as a `T`
+fn reinterpret<S: Plain, T: Plain>(view: ArrayViewD<'_, S>) -> ArrayViewD<'_, T> {
+    assert_same_layout::<S, T>();
+    // Safety: T has the size and alignment of S, and any bits are a valid T, so each element
+    // `view` borrows is equally a T, borrowed for just as long
+    unsafe { view.raw_view().cast::<T>().deref_into_view() }
+}
+
+/// `view` with the bits of each element read and written as a `T`
+fn reinterpret_mut<S: Plain, T: Plain>(mut view: ArrayViewMutD<'_, S>) -> ArrayViewMutD<'_, T> {
+    assert_same_layout::<S, T>();
+    // Safety: as for `reinterpret`, with `view` given up so that only the new view can write
+    unsafe { view.raw_view_mut().cast::<T>().deref_into_view_mut() }
 }
 
 impl<T> SlicedArcArray<T> {
     pub fn from_array(data: ndarray::ArrayD<T>) -> Self {
         Self {
             slices: vec![],
-            unsliced: Arc::new(RwLock::new(data)),
+            permutation: None,
+            unsliced: Storage::Owned(Arc::new(RwLock::new(data))),
         }
     }
 
     /// Borrow the entire array immutably to read it for a moment
     pub fn read<U>(&self, mut readfn: impl FnMut(ArrayViewD<'_, T>) -> U) -> U {
-        let arr = self.unsliced.read().unwrap();
+        let mut out = None;
+        self.unsliced.read(&mut |arr| {
+            let mut arr_slice = arr;
 
-        let mut arr_slice = arr.view();
+            for slice in &self.slices {
+                arr_slice = arr_slice.slice_move(slice);
+            }
 
-        for slice in &self.slices {
-            arr_slice = arr_slice.slice_move(slice);
-        }
+            if let Some(permutation) = &self.permutation {
+                arr_slice = arr_slice.permuted_axes(permutation.as_slice());
+            }
 
-        readfn(arr_slice)
+            out = Some(readfn(arr_slice));
+        });
+        out.expect("Storage reads exactly once")
     }
 
     /// Borrow the entire array mutably for a moment
     pub fn write<U>(&self, writefn: impl Fn(ArrayViewMutD<'_, T>) -> U) -> U {
-        let mut arr = self.unsliced.write().unwrap();
+        let mut out = None;
+        self.unsliced.write(&mut |arr| {
+            let mut arr_slice = arr;
 
-        let mut arr_slice = arr.view_mut();
+            for slice in &self.slices {
+                arr_slice = arr_slice.slice_move(slice);
+            }
 
-        for slice in &self.slices {
-            arr_slice = arr_slice.slice_move(slice);
-        }
+            if let Some(permutation) = &self.permutation {
+                arr_slice = arr_slice.permuted_axes(permutation.as_slice());
+            }
+
+            out = Some(writefn(arr_slice));
+        });
+        out.expect("Storage writes exactly once")
+    }
 
-        writefn(arr_slice)
+    /// Whether `other` views the same data as us, whatever its dtype
+    pub fn shares_data_with<U>(&self, other: &SlicedArcArray<U>) -> bool {
+        self.unsliced.root() == other.unsliced.root()
     }
 
     pub fn append_slice(&self, slice: DynamicSlice, vm: &VirtualMachine) -> PyResult<Self> {
+        let slice = normalize_indices(&slice, &self.shape(), vm)?;
         if let Err(e) = self.read(|sliced| sliced.bounds_check(&slice)) {
             return Err(vm.new_index_error(format!("Slice out of bounds; {e}")));
         }
 
+        // The slices all come before the permutation, so a slice of a transposed view has to
+        // be put in terms of the axes from before it was transposed
+        let (slice, permutation) = match &self.permutation {
+            Some(permutation) => {
+                let (slice, permutation) = unpermute_slice(&slice, permutation);
+                (slice, Some(permutation))
+            }
+            None => (slice, None),
+        };
+
         let mut slices = self.slices.clone();
         slices.push(slice);
 
         Ok(Self {
             slices,
+            permutation: permutation.filter(|permutation| !is_identity(permutation)),
+            unsliced: self.unsliced.clone(),
+        })
+    }
+
+    /// The whole array this is a view into, or None if it isn't a view. An array reading
+    /// another dtype's data is always a view.
+    pub fn base(&self) -> Option<Self> {
+        let reinterpreted = matches!(self.unsliced, Storage::Reinterpreted(_));
+        (reinterpreted || !self.slices.is_empty() || self.permutation.is_some()).then(|| Self {
+            slices: vec![],
+            permutation: None,
             unsliced: self.unsliced.clone(),
         })
     }
 
-    pub fn ndim(&self) -> usize {
-        self.read(|sliced| sliced.ndim())
-    }
+    /// A view with the axes in the order `axes`, or reversed by default. This shares data with
+    /// the original, rather than copying it.
+    pub fn transpose(&self, axes: Option<&[isize]>, vm: &VirtualMachine) -> PyResult<Self> {
+        let ndim = self.ndim();
+        let order: Vec<usize> = match axes {
+            None => (0..ndim).rev().collect(),
+            Some(axes) => {
+                if axes.len() != ndim {
+                    return Err(vm.new_value_error(format!(
+                        "axes {axes:?} don't match an array of {ndim} dimensions"
+                    )));
+                }
+                let order = axes
+                    .iter()
+                    .map(|&axis| normalize_axis(axis, ndim, vm))
+                    .collect::<PyResult<Vec<usize>>>()?;
+                if (0..ndim).any(|axis| !order.contains(&axis)) {
+                    return Err(vm.new_value_error(format!("Repeated axis in {axes:?}")));
+                }
+                order
+            }
+        };
+
+        let permutation: Vec<usize> = match &self.permutation {
+            Some(permutation) => order.iter().map(|&axis| permutation[axis]).collect(),
+            None => order,
+        };
+
+        Ok(Self {
+            slices: self.slices.clone(),
+            permutation: Some(permutation).filter(|permutation| !is_identity(permutation)),
+            unsliced: self.unsliced.clone(),
+        })
+    }
+
+    /// A view with a new length-1 axis at position `axis`, anywhere from 0 to ndim
+    pub fn expand_dims(&self, axis: isize, vm: &VirtualMachine) -> PyResult<Self> {
+        let ndim = self.ndim();
+        let axis = normalize_axis(axis, ndim + 1, vm)?;
+
+        let mut elems = vec![SliceInfoElem::from(..); ndim + 1];
+        elems[axis] = SliceInfoElem::NewAxis;
+        self.append_slice(DynamicSlice::try_from(elems).unwrap(), vm)
+    }
+
+    /// A view reversed along `axis`, or along every axis
+    pub fn flip(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult<Self> {
+        let ndim = self.ndim();
+        let flipped = axis.map(|axis| normalize_axis(axis, ndim, vm)).transpose()?;
+
+        let elems: Vec<SliceInfoElem> = (0..ndim)
+            .map(|axis| {
+                if flipped.is_none_or(|flipped| flipped == axis) {
+                    // In Python's terms, as for `a[::-1]`, for `append_slice` to convert
+                    SliceInfoElem::Slice {
+                        start: -1,
+                        end: None,
+                        step: -1,
+                    }
+                } else {
+                    SliceInfoElem::from(..)
+                }
+            })
+            .collect();
+        self.append_slice(DynamicSlice::try_from(elems).unwrap(), vm)
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.read(|sliced| sliced.ndim())
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.read(|sliced| sliced.shape().to_vec())
+    }
+
+    pub fn length(&self) -> usize {
+        self.read(|sliced| sliced.shape().get(0).copied().unwrap_or(1))
+    }
+}
+
+/// The distinct elements of an array, and where they came from
+#[derive(Debug, Clone)]
+pub struct Unique<T> {
+    /// Each distinct element once, in sorted order
+    pub values: Vec<T>,
+    /// Where in the flattened array each value first occurs
+    pub indices: Vec<i64>,
+    /// For each element of the flattened array, which value it is
+    pub inverse: Vec<i64>,
+    /// How many times each value occurs
+    pub counts: Vec<i64>,
+}
+
+impl<T: Plain> SlicedArcArray<T> {
+    /// A view of the same elements with their bits read as `U`, which must be the same size.
+    /// Writes through either array show in the other.
+    pub fn view_as<U: Plain>(&self) -> SlicedArcArray<U> {
+        SlicedArcArray {
+            slices: self.slices.clone(),
+            permutation: self.permutation.clone(),
+            unsliced: Storage::Reinterpreted(Arc::new(Reinterpreted {
+                source: self.unsliced.clone(),
+            })),
+        }
+    }
+}
+
+impl<T: Clone> SlicedArcArray<T> {
+    pub fn sliced_copy(&self) -> Self {
+        self.read(|sliced| Self::from_array(sliced.to_owned()))
+    }
+
+    /// Views of the consecutive pieces along `axis` between each of `points`. Negative points
+    /// count from the end, and points out of order or past the end give empty pieces, as in
+    /// numpy.
+    pub fn split_at(
+        &self,
+        points: &[isize],
+        axis: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<Self>> {
+        let ndim = self.ndim();
+        let len = self.shape()[axis];
+        let clamp = |point: isize| {
+            if point < 0 {
+                (point + len as isize).max(0) as usize
+            } else {
+                (point as usize).min(len)
+            }
+        };
+
+        let mut bounds = vec![0];
+        bounds.extend(points.iter().map(|&point| clamp(point)));
+        bounds.push(len);
+
+        bounds
+            .windows(2)
+            .map(|bounds| {
+                let (start, end) = (bounds[0], bounds[1].max(bounds[0]));
+                let mut elems = vec![SliceInfoElem::from(..); ndim];
+                elems[axis] = SliceInfoElem::from(start..end);
+                let slice = DynamicSlice::try_from(elems).expect("An element for each axis");
+                self.append_slice(slice, vm)
+            })
+            .collect()
+    }
+
+    /// A copy with `widths[axis] = (before, after)` elements of `value` around each axis
+    pub fn pad(&self, widths: &[(usize, usize)], value: T) -> ArrayD<T> {
+        self.read(|sliced| {
+            let shape: Vec<usize> = sliced
+                .shape()
+                .iter()
+                .zip(widths)
+                .map(|(len, (before, after))| before + len + after)
+                .collect();
+            let interior: Vec<SliceInfoElem> = sliced
+                .shape()
+                .iter()
+                .zip(widths)
+                .map(|(&len, &(before, _))| SliceInfoElem::from(before..before + len))
+                .collect();
+
+            let mut padded = ArrayD::from_elem(shape, value);
+            padded.slice_mut(interior.as_slice()).assign(&sliced);
+            padded
+        })
+    }
+
+    /// A copy shifted cyclically by `shift` places along `axis`, or through the flattened
+    /// elements (keeping the shape) if there's no axis
+    pub fn roll(
+        &self,
+        shift: isize,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult<ArrayD<T>> {
+        self.read(|sliced| match axis {
+            None => {
+                let flat: Vec<T> = sliced.iter().cloned().collect();
+                let len = flat.len();
+                let offset = roll_offset(shift, len);
+                let rolled = (0..len).map(|i| flat[(i + len - offset) % len].clone());
+                Ok(ArrayD::from_shape_vec(sliced.raw_dim(), rolled.collect())
+                    .expect("Length matches the shape"))
+            }
+            Some(axis) => {
+                let axis = normalize_axis(axis, sliced.ndim(), vm)?;
+                let len = sliced.len_of(Axis(axis));
+                let offset = roll_offset(shift, len);
+                Ok(ArrayD::from_shape_fn(sliced.raw_dim(), |mut idx| {
+                    idx[axis] = (idx[axis] + len - offset) % len;
+                    sliced[idx].clone()
+                }))
+            }
+        })
+    }
+
+    /// Copies of the elements at `indices` along `axis`, or through the flattened elements if
+    /// there's no axis
+    pub fn take(
+        &self,
+        indices: &[isize],
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult<ArrayD<T>> {
+        self.read(|sliced| match axis {
+            None => select_indices(flattened(sliced).view(), 0, indices, vm),
+            Some(axis) => {
+                let axis = normalize_axis(axis, sliced.ndim(), vm)?;
+                select_indices(sliced, axis, indices, vm)
+            }
+        })
+    }
+
+    /// Copies of the elements at `indices` along `axis`, as `argsort` or a reduction with
+    /// `keepdims=True` gives them. `indices` has as many axes as we do, and broadcasts against us
+    /// along the others. With no axis, we're flattened first.
+    pub fn take_along_axis(
+        &self,
+        indices: &SlicedArcArray<i64>,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult<ArrayD<T>> {
+        let indices = indices.read(|indices| indices.to_owned());
+        self.read(|sliced| {
+            let (arr, axis) = match axis {
+                None => (flattened(sliced), 0),
+                Some(axis) => (sliced.to_owned(), normalize_axis(axis, sliced.ndim(), vm)?),
+            };
+            if indices.ndim() != arr.ndim() {
+                return Err(vm.new_value_error(format!(
+                    "take_along_axis: Indices of shape {:?} must have as many axes as shape {:?}",
+                    indices.shape(),
+                    arr.shape(),
+                )));
+            }
+
+            // The other axes broadcast together, leaving each its own length along `axis`
+            let len = arr.len_of(Axis(axis));
+            let mut arr_shape = arr.shape().to_vec();
+            let mut index_shape = indices.shape().to_vec();
+            arr_shape[axis] = 1;
+            index_shape[axis] = 1;
+            let mut shape = broadcast_shape(&[&arr_shape, &index_shape], vm)?;
+            shape[axis] = indices.len_of(Axis(axis));
+            let indices = broadcast_view(&indices, &shape, vm)?;
+            shape[axis] = len;
+            let arr = broadcast_view(&arr, &shape, vm)?;
+
+            let mut taken = Vec::with_capacity(indices.len());
+            for (mut idx, &index) in indices.indexed_iter() {
+                idx[axis] = normalize_index(index as isize, len, axis, vm)?;
+                taken.push(arr[idx].clone());
+            }
+            Ok(ArrayD::from_shape_vec(indices.raw_dim(), taken).expect("Length matches the shape"))
+        })
+    }
+
+    /// Copies of the elements where `mask` is true, as boolean indexing picks them. The mask
+    /// covers our leading axes, each true entry picking out everything along the rest, so a mask
+    /// of our whole shape gives a 1-D array.
+    pub fn masked(&self, mask: &SlicedArcArray<bool>, vm: &VirtualMachine) -> PyResult<ArrayD<T>> {
+        let mask = mask.read(|mask| mask.to_owned());
+        self.read(|sliced| {
+            let shape = sliced.shape();
+            if mask.ndim() > shape.len() || mask.shape() != &shape[..mask.ndim()] {
+                return Err(vm.new_index_error(format!(
+                    "Boolean index of shape {:?} does not match array of shape {shape:?}",
+                    mask.shape(),
+                )));
+            }
+
+            let inner = &shape[mask.ndim()..];
+            let inner_len: usize = inner.iter().product();
+            let elems: Vec<T> = sliced.iter().cloned().collect();
+
+            let mut picked = Vec::new();
+            let mut count = 0;
+            for (i, _) in mask.iter().enumerate().filter(|&(_, &keep)| keep) {
+                picked.extend_from_slice(&elems[i * inner_len..(i + 1) * inner_len]);
+                count += 1;
+            }
+
+            let shape: Vec<usize> = std::iter::once(count).chain(inner.iter().copied()).collect();
+            Ok(ArrayD::from_shape_vec(shape, picked).expect("Length matches the shape"))
+        })
+    }
+
+    /// Writes `values` at `indices` into the flattened elements, in place. The values repeat
+    /// if there are fewer of them than indices.
+    pub fn put(&self, indices: &[isize], values: &[T], vm: &VirtualMachine) -> PyResult<()> {
+        self.write(|mut sliced| {
+            let len = sliced.len();
+            let indices = indices
+                .iter()
+                .map(|&index| normalize_index(index, len, 0, vm))
+                .collect::<PyResult<Vec<usize>>>()?;
+
+            let mut elems: Vec<&mut T> = sliced.iter_mut().collect();
+            for (index, value) in indices.into_iter().zip(values.iter().cycle()) {
+                *elems[index] = value.clone();
+            }
+            Ok(())
+        })
+    }
+
+    /// The element of a 0-D or single element array, or None for any other size
+    pub fn only_element(&self) -> Option<T> {
+        self.read(|sliced| match sliced.first() {
+            Some(x) if sliced.len() == 1 => Some(x.clone()),
+            _ => None,
+        })
+    }
+
+    /// Writes `arr` over our contents. The shape may only change for an array that owns its data
+    /// and has no views into it, as their slices would no longer fit.
+    pub fn store(&self, arr: ArrayD<T>, vm: &VirtualMachine) -> PyResult<()> {
+        if self.read(|sliced| sliced.shape() == arr.shape()) {
+            self.write(|mut sliced| sliced.assign(&arr));
+            return Ok(());
+        }
+
+        match &self.unsliced {
+            Storage::Owned(unsliced)
+                if self.base().is_none() && Arc::strong_count(unsliced) == 1 =>
+            {
+                *unsliced.write().unwrap() = arr;
+                Ok(())
+            }
+            _ => Err(vm.new_value_error(format!(
+                "Cannot store shape {:?} in place of shape {:?}, as other arrays view this data",
+                arr.shape(),
+                self.shape(),
+            ))),
+        }
+    }
+
+    /// Changes the shape in place, cutting the flattened elements short or padding them out with
+    /// `fill`. As with `store`, the shape of a view, or of data with views into it, can't change.
+    pub fn resize(&self, shape: Vec<usize>, fill: T, vm: &VirtualMachine) -> PyResult<()> {
+        if self.base().is_some() {
+            return Err(vm.new_value_error(
+                "Cannot resize a view, as it doesn't own its data".to_owned(),
+            ));
+        }
+
+        let len = shape.iter().product();
+        let mut elems: Vec<T> = self.read(|sliced| sliced.iter().cloned().collect());
+        elems.resize(len, fill);
+        let resized = ArrayD::from_shape_vec(shape, elems).expect("Length matches the shape");
+        self.store(resized, vm)
+    }
+
+    /// A new array of `f` applied to each element
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> SlicedArcArray<U> {
+        self.read(|sliced| SlicedArcArray::from_array(sliced.mapv(&f)))
+    }
+
+    /// Copy of the `offset`th diagonal of a 2-D array; positive offsets are above the main one
+    pub fn diagonal(&self, offset: isize, vm: &VirtualMachine) -> PyResult<ArrayD<T>> {
+        self.read(|sliced| {
+            let matrix = sliced
+                .into_dimensionality::<Ix2>()
+                .map_err(|_| vm.new_value_error("diagonal requires a 2-D array".into()))?;
+
+            let (rows, cols) = matrix.dim();
+            let (first_row, first_col) = if offset >= 0 {
+                (0, offset.unsigned_abs())
+            } else {
+                (offset.unsigned_abs(), 0)
+            };
+            let len = rows
+                .saturating_sub(first_row)
+                .min(cols.saturating_sub(first_col));
+
+            Ok(ArrayD::from_shape_fn(IxDyn(&[len]), |i| {
+                matrix[[first_row + i[0], first_col + i[0]]].clone()
+            }))
+        })
+    }
+
+    /// A row-major copy with the new `shape`, in which one length may be -1 to have it inferred
+    pub fn reshape(&self, shape: &[isize], vm: &VirtualMachine) -> PyResult<Self> {
+        let size = self.read(|sliced| sliced.len());
+        let bad_shape = || {
+            vm.new_value_error(format!(
+                "Cannot reshape array of size {size} into shape {shape:?}"
+            ))
+        };
+
+        let inferred = shape.iter().filter(|&&len| len == -1).count();
+        if inferred > 1 || shape.iter().any(|&len| len < -1) {
+            return Err(bad_shape());
+        }
+
+        let known: usize = shape
+            .iter()
+            .filter(|&&len| len != -1)
+            .map(|&len| len as usize)
+            .product();
+        let new_shape: Vec<usize> = shape
+            .iter()
+            .map(|&len| match len {
+                -1 if known != 0 => Ok(size / known),
+                -1 => Err(bad_shape()),
+                len => Ok(len as usize),
+            })
+            .collect::<PyResult<_>>()?;
+
+        let elements = self.read(|sliced| sliced.iter().cloned().collect());
+        ArrayD::from_shape_vec(IxDyn(&new_shape), elements)
+            .map(Self::from_array)
+            .map_err(|_| bad_shape())
+    }
+
+    /// A copy without the length-1 axes, or without just `axis`, which must be length 1
+    pub fn squeeze(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult<Self> {
+        self.read(|sliced| {
+            let mut out = sliced.to_owned();
+            match axis {
+                Some(axis) => {
+                    let axis = Axis(normalize_axis(axis, out.ndim(), vm)?);
+                    if out.len_of(axis) != 1 {
+                        return Err(vm.new_value_error(format!(
+                            "Cannot squeeze out axis {} of length {}",
+                            axis.index(),
+                            out.len_of(axis),
+                        )));
+                    }
+                    out = out.index_axis_move(axis, 0);
+                }
+                None => {
+                    // Backwards, so removing an axis doesn't renumber those still to check
+                    for axis in (0..out.ndim()).rev().map(Axis) {
+                        if out.len_of(axis) == 1 {
+                            out = out.index_axis_move(axis, 0);
+                        }
+                    }
+                }
+            }
+            Ok(Self::from_array(out))
+        })
+    }
+
+    /// Running accumulation of `f(previous, current)` along `axis`, or over the flattened array
+    /// (giving a 1-D result) when there is no axis
+    pub fn accumulate(
+        &self,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+        f: impl Fn(T, T) -> T,
+    ) -> PyResult<Self> {
+        self.read(|sliced| {
+            let (mut out, axis) = match axis {
+                None => (flattened(sliced), 0),
+                Some(axis) => (sliced.to_owned(), normalize_axis(axis, sliced.ndim(), vm)?),
+            };
+
+            out.accumulate_axis_inplace(Axis(axis), |prev, cur| {
+                *cur = f(prev.clone(), cur.clone())
+            });
+            Ok(Self::from_array(out))
+        })
+    }
+}
+
+impl<T: Clone + ToPyObject> SlicedArcArray<T>
+where
+    Self: GenericArray,
+{
+    /// Folds every element into `init` with `f` giving a scalar, or folds along `axis` giving a
+    /// reduced array. Empty arrays and lanes give `init`.
+    pub fn fold(
+        &self,
+        axis: Option<isize>,
+        init: T,
+        vm: &VirtualMachine,
+        f: impl Fn(T, T) -> T,
+    ) -> PyResult {
+        self.read(|sliced| match axis {
+            None => Ok(sliced.iter().cloned().fold(init.clone(), &f).to_pyobject(vm)),
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                let folded =
+                    sliced.fold_axis(axis, init.clone(), |acc, x| f(acc.clone(), x.clone()));
+                Ok(Self::from_array(folded).cast().to_pyobject(vm))
+            }
+        })
+    }
+}
+
+impl<T: PyRepr> SlicedArcArray<T>
+where
+    SlicedArcArray<T>: GenericArray,
+{
+    /// numpy's repr. The shape is given too where the body doesn't show it, as when it's
+    /// summarized or empty.
+    pub fn repr(&self) -> String {
+        let prefix = "array(";
+        let (body, hidden_shape) = self.read(|sliced| {
+            let summarized = is_summarized(&sliced, &print_options());
+            let hidden = summarized || (sliced.is_empty() && sliced.ndim() > 1);
+            let shape = hidden.then(|| shape_repr(sliced.shape()));
+            (format_array(sliced, prefix.len()), shape)
+        });
+        let dtype = Self::DTYPE.stringy_key();
+        match hidden_shape {
+            Some(shape) => format!("{prefix}{body}, shape={shape}, dtype='{dtype}')"),
+            None => format!("{prefix}{body}, dtype='{dtype}')"),
+        }
+    }
+}
+
+impl<T: ToPyObject + Copy> SlicedArcArray<T> {
+    /// getitem, as implemented in the rustpython interface
+    pub fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult
+    where
+        SlicedArcArray<T>: GenericArray,
+    {
+        // As in numpy, an index with an Ellipsis gives an array even when it's 0-D, so
+        // `a[i, ...]` opts out of unwrapping to a scalar
+        let keep_array = index_has_ellipsis(&needle, vm);
+        let slice = py_index_to_sliceinfo(needle, self.ndim(), vm)?;
+        let sliced_self = self.append_slice(slice, vm)?;
+
+        sliced_self.read(|sliced_array| {
+            if sliced_array.ndim() == 0 && !keep_array {
+                Ok(sliced_array.get([]).copied().unwrap().to_pyobject(vm))
+            } else {
+                Ok(sliced_self.cast().to_pyobject(vm))
+            }
+        })
+    }
+}
+
+impl<T: Element + PartialOrd> SlicedArcArray<T>
+where
+    SlicedArcArray<bool>: GenericArray,
+    SlicedArcArray<i64>: GenericArray,
+{
+    /// Flat index of the largest element, or an array of the indices of the largest elements
+    /// along `axis`. NaN counts as the largest, and ties go to the first occurrence.
+    pub fn argmax(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.arg_reduce(axis, "argmax", false, vm, |x, best| {
+            !best.is_nan() && (x.is_nan() || x > best)
+        })
+    }
+
+    /// As `argmax`, but for the smallest element. NaN still wins.
+    pub fn argmin(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.arg_reduce(axis, "argmin", false, vm, |x, best| {
+            !best.is_nan() && (x.is_nan() || x < best)
+        })
+    }
+
+    /// As `argmax`, but ignoring NaNs. Raises ValueError if everything considered is NaN.
+    pub fn nanargmax(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.arg_reduce(axis, "nanargmax", true, vm, |x, best| x > best)
+    }
+
+    /// As `argmin`, but ignoring NaNs. Raises ValueError if everything considered is NaN.
+    pub fn nanargmin(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.arg_reduce(axis, "nanargmin", true, vm, |x, best| x < best)
+    }
+
+    /// Whether all elements (or any, when `all` is false) are truthy, as a Python bool or as
+    /// a mask reduced along `axis`
+    pub fn truth(&self, axis: Option<isize>, all: bool, vm: &VirtualMachine) -> PyResult {
+        self.read(|sliced| match axis {
+            None => Ok(vm.ctx.new_bool(truth_of(sliced.iter().copied(), all)).into()),
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+
+                let truth = sliced.map_axis(axis, |lane| truth_of(lane.iter().copied(), all));
+                Ok(SlicedArcArray::from_array(truth).cast().to_pyobject(vm))
+            }
+        })
+    }
+
+    /// How many elements are nonzero: in all as an int, or along `axis` as an int64 array
+    pub fn count_nonzero(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.read(|sliced| match axis {
+            None => {
+                let count = sliced.iter().filter(|x| x.is_nonzero()).count();
+                Ok(vm.ctx.new_int(count).into())
+            }
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                let counts = sliced
+                    .map_axis(axis, |lane| lane.iter().filter(|x| x.is_nonzero()).count() as i64);
+                Ok(SlicedArcArray::from_array(counts).cast().to_pyobject(vm))
+            }
+        })
+    }
+
+    fn arg_reduce(
+        &self,
+        axis: Option<isize>,
+        name: &str,
+        skip_nan: bool,
+        vm: &VirtualMachine,
+        better: impl Fn(T, T) -> bool,
+    ) -> PyResult {
+        let empty_err = || vm.new_value_error(format!("Attempt to get {name} of an empty array"));
+        let all_nan_err = || vm.new_value_error("All-NaN slice encountered".to_owned());
+        let candidates = |x: &T| !(skip_nan && x.is_nan());
+
+        self.read(|sliced| match axis {
+            None => {
+                if sliced.is_empty() {
+                    return Err(empty_err());
+                }
+                arg_best(sliced.iter().copied(), candidates, &better)
+                    .map(|index| vm.ctx.new_int(index).into())
+                    .ok_or_else(all_nan_err)
+            }
+            Some(axis) => {
+                let axis = normalize_axis(axis, sliced.ndim(), vm)?;
+                if sliced.len_of(Axis(axis)) == 0 {
+                    return Err(empty_err());
+                }
+
+                let indices = sliced.map_axis(Axis(axis), |lane| {
+                    arg_best(lane.iter().copied(), candidates, &better)
+                });
+                if indices.iter().any(Option::is_none) {
+                    return Err(all_nan_err());
+                }
+
+                let indices = indices.mapv(|index| index.unwrap_or(0) as i64);
+                Ok(SlicedArcArray::from_array(indices).cast().to_pyobject(vm))
+            }
+        })
+    }
+}
+
+impl<T: Element + ToPyObject> SlicedArcArray<T>
+where
+    Self: GenericArray,
+{
+    /// The largest element as a scalar, or the largest along `axis`. NaN wins, so any NaN gives
+    /// NaN, as in numpy.
+    pub fn max(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.extreme(axis, "maximum", false, vm, |x, best| {
+            !best.is_nan() && (x.is_nan() || x > best)
+        })
+    }
+
+    /// As `max`, but for the smallest element. NaN still wins.
+    pub fn min(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.extreme(axis, "minimum", false, vm, |x, best| {
+            !best.is_nan() && (x.is_nan() || x < best)
+        })
+    }
+
+    /// As `max`, but ignoring NaNs. Only when everything is NaN is the result NaN.
+    pub fn nanmax(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.extreme(axis, "maximum", true, vm, |x, best| x > best)
+    }
+
+    /// As `min`, but ignoring NaNs. Only when everything is NaN is the result NaN.
+    pub fn nanmin(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.extreme(axis, "minimum", true, vm, |x, best| x < best)
+    }
+
+    fn extreme(
+        &self,
+        axis: Option<isize>,
+        name: &str,
+        skip_nan: bool,
+        vm: &VirtualMachine,
+        better: impl Fn(T, T) -> bool,
+    ) -> PyResult {
+        let empty_err = || {
+            vm.new_value_error(format!(
+                "zero-size array to reduction operation {name} which has no identity"
+            ))
+        };
+
+        self.read(|sliced| match axis {
+            None => best_of(sliced.iter().copied(), skip_nan, &better)
+                .map(|best| best.to_pyobject(vm))
+                .ok_or_else(empty_err),
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                if sliced.len_of(axis) == 0 {
+                    return Err(empty_err());
+                }
+                let extremes = sliced.map_axis(axis, |lane| {
+                    best_of(lane.iter().copied(), skip_nan, &better).expect("Lane isn't empty")
+                });
+                Ok(Self::from_array(extremes).cast().to_pyobject(vm))
+            }
+        })
+    }
+}
+
+/// The best of `elems` by `better`, or None if there are none. With `skip_nan`, NaNs are
+/// passed over unless they're all there is, when the first stands in.
+fn best_of<T: Element>(
+    elems: impl Iterator<Item = T>,
+    skip_nan: bool,
+    better: impl Fn(T, T) -> bool,
+) -> Option<T> {
+    let mut first = None;
+    let mut best = None;
+    for x in elems {
+        first.get_or_insert(x);
+        if skip_nan && x.is_nan() {
+            continue;
+        }
+        match best {
+            Some(best_x) if !better(x, best_x) => {}
+            _ => best = Some(x),
+        }
+    }
+    best.or(first)
+}
+
+impl<T: Element> SlicedArcArray<T> {
+    /// `f` of the sorted elements, all together giving a 0-D result, or of each lane along
+    /// `axis`. NaNs sort last.
+    pub fn map_sorted_lanes<U>(
+        &self,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+        f: impl Fn(&[T]) -> U,
+    ) -> PyResult<ArrayD<U>> {
+        let sorted_f = |mut elems: Vec<T>| {
+            elems.sort_by(T::sort_cmp);
+            f(&elems)
+        };
+
+        self.read(|sliced| match axis {
+            None => {
+                let out = sorted_f(sliced.iter().copied().collect());
+                Ok(ArrayD::from_shape_vec(IxDyn(&[]), vec![out]).expect("One element for 0-D"))
+            }
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                Ok(sliced.map_axis(axis, |lane| sorted_f(lane.to_vec())))
+            }
+        })
+    }
+
+    /// The distinct elements in sorted order, as `unique` gives them. NaNs count as one value,
+    /// which sorts last.
+    pub fn unique(&self) -> Unique<T> {
+        let mut elems: Vec<(usize, T)> =
+            self.read(|sliced| sliced.iter().copied().enumerate().collect());
+        // Stable, so the first of each run of equal elements is its first occurrence
+        elems.sort_by(|(_, a), (_, b)| a.sort_cmp(b));
+
+        let mut unique = Unique {
+            values: Vec::new(),
+            indices: Vec::new(),
+            inverse: vec![0; elems.len()],
+            counts: Vec::new(),
+        };
+        for (i, x) in elems {
+            match unique.values.last() {
+                Some(last) if last.sort_cmp(&x).is_eq() => {
+                    *unique.counts.last_mut().expect("A count per value") += 1;
+                }
+                _ => {
+                    unique.values.push(x);
+                    unique.indices.push(i as i64);
+                    unique.counts.push(1);
+                }
+            }
+            unique.inverse[i] = unique.values.len() as i64 - 1;
+        }
+        unique
+    }
+
+    /// Indices of the nonzero elements in row-major order, as a list for each axis
+    pub fn nonzero(&self) -> Vec<Vec<i64>> {
+        self.read(|sliced| {
+            let mut indices = vec![Vec::new(); sliced.ndim()];
+            for (index, x) in sliced.indexed_iter() {
+                if x.is_nonzero() {
+                    for (axis, &i) in index.slice().iter().enumerate() {
+                        indices[axis].push(i as i64);
+                    }
+                }
+            }
+            indices
+        })
+    }
+
+    /// Whether any element equals `value`. NaN finds NaN here, unlike with `==`.
+    pub fn contains(&self, value: T) -> bool {
+        self.read(|sliced| {
+            sliced
+                .iter()
+                .any(|&x| x == value || (x.is_nan() && value.is_nan()))
+        })
+    }
+
+    /// Sorts in place along `axis`, putting NaNs last
+    pub fn sort_axis(&self, axis: isize, vm: &VirtualMachine) -> PyResult<()> {
+        let axis = Axis(normalize_axis(axis, self.ndim(), vm)?);
+        self.write(|mut sliced| {
+            for mut lane in sliced.lanes_mut(axis) {
+                let mut sorted: Vec<T> = lane.iter().copied().collect();
+                sorted.sort_by(T::sort_cmp);
+                lane.iter_mut().zip(sorted).for_each(|(x, y)| *x = y);
+            }
+        });
+        Ok(())
+    }
+
+    /// Indices which would sort along `axis`, or the flattened array when there is no axis.
+    /// The sort is stable, so equal elements keep their order.
+    pub fn argsort(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult<ArrayD<usize>> {
+        self.read(|sliced| {
+            let (data, axis) = match axis {
+                None => (flattened(sliced), 0),
+                Some(axis) => (sliced.to_owned(), normalize_axis(axis, sliced.ndim(), vm)?),
+            };
+
+            let mut indices = ArrayD::<usize>::zeros(data.raw_dim());
+            Zip::from(indices.lanes_mut(Axis(axis)))
+                .and(data.lanes(Axis(axis)))
+                .for_each(|mut out, lane| {
+                    let mut order: Vec<usize> = (0..lane.len()).collect();
+                    order.sort_by(|&i, &j| lane[i].sort_cmp(&lane[j]));
+                    out.iter_mut().zip(order).for_each(|(x, i)| *x = i);
+                });
+            Ok(indices)
+        })
+    }
+}
+
+/// Rewrites `slice`, which indexes the axes of a view permuted by `permutation`, to index the
+/// axes from before the permutation instead. Gives that slice, and the permutation to apply
+/// after it in place of the old one.
+fn unpermute_slice(slice: &DynamicSlice, permutation: &[usize]) -> (DynamicSlice, Vec<usize>) {
+    let mut unpermuted = vec![SliceInfoElem::from(..); permutation.len()];
+    // Where each axis of the result comes from: an unpermuted axis, or the nth new axis
+    let mut sources: Vec<Result<usize, usize>> = vec![];
+    let mut axes = permutation.iter();
+    let mut new_axes = 0;
+
+    for &elem in slice.iter() {
+        if elem == SliceInfoElem::NewAxis {
+            sources.push(Err(new_axes));
+            new_axes += 1;
+            continue;
+        }
+
+        let axis = *axes.next().expect("Slice has an element per axis");
+        unpermuted[axis] = elem;
+        if let SliceInfoElem::Slice { .. } = elem {
+            sources.push(Ok(axis));
+        }
+    }
+
+    // Indexing drops axes, but the rest keep their order, and new axes go on the end
+    let kept: Vec<usize> = (0..permutation.len())
+        .filter(|&axis| !matches!(unpermuted[axis], SliceInfoElem::Index(_)))
+        .collect();
+    unpermuted.extend(std::iter::repeat(SliceInfoElem::NewAxis).take(new_axes));
+
+    let permutation = sources
+        .into_iter()
+        .map(|source| match source {
+            Ok(axis) => kept.iter().position(|&kept| kept == axis).expect("Axis was kept"),
+            Err(new_axis) => kept.len() + new_axis,
+        })
+        .collect();
+
+    (DynamicSlice::try_from(unpermuted).unwrap(), permutation)
+}
+
+fn is_identity(permutation: &[usize]) -> bool {
+    permutation.iter().enumerate().all(|(i, &axis)| i == axis)
+}
+
+/// Copies `view` out as a 1-D array, in row-major order
+pub fn flattened<T: Clone>(view: ArrayViewD<'_, T>) -> ArrayD<T> {
+    ArrayD::from_shape_vec(IxDyn(&[view.len()]), view.iter().cloned().collect())
+        .expect("Flattened length matches")
+}
+
+/// `view` as a 1-D array, reading the elements in `order`
+pub fn flattened_in_order<T: Clone>(view: ArrayViewD<'_, T>, order: Order) -> ArrayD<T> {
+    let fortran = match order {
+        Order::C => false,
+        Order::F => true,
+        Order::Keep => !view.is_standard_layout() && view.t().is_standard_layout(),
+    };
+
+    if fortran {
+        // Column-major order is the row-major order of the transpose
+        flattened(view.reversed_axes())
+    } else {
+        flattened(view)
+    }
+}
+
+fn select_indices<T: Clone>(
+    view: ArrayViewD<'_, T>,
+    axis: usize,
+    indices: &[isize],
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let len = view.len_of(Axis(axis));
+    let indices = indices
+        .iter()
+        .map(|&index| normalize_index(index, len, axis, vm))
+        .collect::<PyResult<Vec<usize>>>()?;
+    Ok(view.select(Axis(axis), &indices))
+}
+
+/// The `q`th quantile (within 0 to 1) of `sorted`, interpolating linearly between the elements
+/// either side as numpy does by default. Any NaN makes it NaN, as does having no elements.
+pub fn sorted_quantile<T: Float>(sorted: &[T], q: f64) -> T {
+    match sorted.last() {
+        Some(last) if !last.is_nan() => {}
+        _ => return T::nan(),
+    }
+
+    let pos = q * (sorted.len() - 1) as f64;
+    let below = pos.floor() as usize;
+    let above = (below + 1).min(sorted.len() - 1);
+    let (a, b) = (sorted[below], sorted[above]);
+    let t = T::from(pos - below as f64).expect("Float from f64");
+
+    // Interpolating from whichever end is nearer, as numpy does, is exact at both ends
+    if t < T::from(0.5).expect("Float from f64") {
+        a + (b - a) * t
+    } else {
+        b - (b - a) * (T::one() - t)
+    }
+}
+
+/// The middle element of `sorted`, or the mean of the middle two for an even count. Any NaN
+/// makes it NaN, as does having no elements.
+pub fn sorted_median<T: Float>(sorted: &[T]) -> T {
+    match sorted.last() {
+        Some(last) if !last.is_nan() => {}
+        _ => return T::nan(),
+    }
+
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        (sorted[mid - 1] + sorted[mid]) / (T::one() + T::one())
+    }
+}
+
+fn truth_of<T: Element>(mut iter: impl Iterator<Item = T>, all: bool) -> bool {
+    if all {
+        iter.all(T::is_nonzero)
+    } else {
+        iter.any(T::is_nonzero)
+    }
+}
+
+/// Position of the first of the `candidates` which no later candidate is `better` than
+fn arg_best<T: Copy>(
+    iter: impl Iterator<Item = T>,
+    candidates: impl Fn(&T) -> bool,
+    better: impl Fn(T, T) -> bool,
+) -> Option<usize> {
+    let mut best: Option<(usize, T)> = None;
+    for (index, x) in iter.enumerate().filter(|(_, x)| candidates(x)) {
+        match best {
+            Some((_, best_x)) if !better(x, best_x) => {}
+            _ => best = Some((index, x)),
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+impl<T: Float + FromPrimitive + ToPyObject> SlicedArcArray<T>
+where
+    Self: GenericArray,
+{
+    /// Variance with `ddof` delta degrees of freedom, as a scalar or reduced along `axis`
+    pub fn var(&self, axis: Option<isize>, ddof: isize, vm: &VirtualMachine) -> PyResult {
+        self.variance(axis, ddof, vm, |var| var)
+    }
+
+    /// Standard deviation with `ddof` delta degrees of freedom, as a scalar or reduced along `axis`
+    pub fn std(&self, axis: Option<isize>, ddof: isize, vm: &VirtualMachine) -> PyResult {
+        self.variance(axis, ddof, vm, T::sqrt)
+    }
+
+    fn variance(
+        &self,
+        axis: Option<isize>,
+        ddof: isize,
+        vm: &VirtualMachine,
+        finish: impl Fn(T) -> T,
+    ) -> PyResult {
+        if ddof < 0 {
+            return Err(vm.new_value_error(format!("ddof must not be negative, got {ddof}")));
+        }
+
+        // numpy divides by max(n - ddof, 0) where ndarray would panic, so clamp ddof to n
+        let ddof_for = |n: usize| T::from_usize(n.min(ddof as usize)).expect("Float from usize");
+
+        self.read(|sliced| match axis {
+            None => Ok(finish(sliced.var(ddof_for(sliced.len()))).to_pyobject(vm)),
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                let var = sliced.var_axis(axis, ddof_for(sliced.len_of(axis)));
+                Ok(Self::from_array(var.mapv(&finish)).cast().to_pyobject(vm))
+            }
+        })
+    }
+
+    /// Sum ignoring NaNs, as a scalar or reduced along `axis`
+    pub fn nansum(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.nan_reduce(axis, vm, |sum, _| sum)
+    }
+
+    /// Mean ignoring NaNs, as a scalar or reduced along `axis`. All NaNs gives NaN.
+    pub fn nanmean(&self, axis: Option<isize>, vm: &VirtualMachine) -> PyResult {
+        self.nan_reduce(axis, vm, |sum, count| {
+            sum / T::from_usize(count).expect("Float from usize")
+        })
+    }
+
+    /// Reduces the sum and count of the non-NaN elements with `finish`
+    fn nan_reduce(
+        &self,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+        finish: impl Fn(T, usize) -> T,
+    ) -> PyResult {
+        let reduce = |iter: &mut dyn Iterator<Item = T>| {
+            let (sum, count) = iter
+                .filter(|x| !x.is_nan())
+                .fold((T::zero(), 0), |(sum, count), x| (sum + x, count + 1));
+            finish(sum, count)
+        };
+
+        self.read(|sliced| match axis {
+            None => Ok(reduce(&mut sliced.iter().copied()).to_pyobject(vm)),
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+                let reduced = sliced.map_axis(axis, |lane| reduce(&mut lane.iter().copied()));
+                Ok(Self::from_array(reduced).cast().to_pyobject(vm))
+            }
+        })
+    }
+
+    /// Trapezoidal integral along `axis`, with samples at the positions `x` or spaced `dx`
+    /// apart. Integrating a 1-D array gives a scalar.
+    pub fn trapz(
+        &self,
+        x: Option<&SlicedArcArray<T>>,
+        dx: T,
+        axis: isize,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let x = sample_positions(x, vm)?;
+
+        self.read(|y| {
+            let axis = Axis(normalize_axis(axis, y.ndim(), vm)?);
+            let n = y.len_of(axis);
+            check_sample_count(x.as_deref(), n, axis, vm)?;
+
+            let integral = y.map_axis(axis, |lane| {
+                (1..n).fold(T::zero(), |acc, i| {
+                    acc + trapezoid(&lane, x.as_deref(), dx, i)
+                })
+            });
+            Ok(scalar_or_array(integral, vm))
+        })
+    }
+
+    /// Running trapezoidal integral along `axis`, one shorter than `y` unless `initial` is given
+    /// to start it off
+    pub fn cumulative_trapezoid(
+        &self,
+        x: Option<&SlicedArcArray<T>>,
+        dx: T,
+        initial: Option<T>,
+        axis: isize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Self> {
+        let x = sample_positions(x, vm)?;
+
+        self.read(|y| {
+            let axis = Axis(normalize_axis(axis, y.ndim(), vm)?);
+            let n = y.len_of(axis);
+            check_sample_count(x.as_deref(), n, axis, vm)?;
+
+            let offset = usize::from(initial.is_some());
+            let mut shape = y.shape().to_vec();
+            shape[axis.index()] = n.saturating_sub(1) + offset;
+
+            let mut out = ArrayD::from_elem(shape, T::zero());
+            Zip::from(out.lanes_mut(axis))
+                .and(y.lanes(axis))
+                .for_each(|mut out, lane| {
+                    if let Some(initial) = initial {
+                        out[0] = initial;
+                    }
+                    let mut total = T::zero();
+                    for i in 1..n {
+                        total = total + trapezoid(&lane, x.as_deref(), dx, i);
+                        out[i - 1 + offset] = total;
+                    }
+                });
+            Ok(Self::from_array(out))
+        })
+    }
+
+    /// Derivative along `axis`, with samples at the positions `x` or spaced `dx` apart. Inside,
+    /// it's the second order difference for uneven spacing, which for even spacing is the
+    /// central difference; at the ends, it's one-sided.
+    pub fn gradient(
+        &self,
+        x: Option<&[T]>,
+        dx: T,
+        axis: isize,
+        vm: &VirtualMachine,
+    ) -> PyResult<ArrayD<T>> {
+        self.read(|f| {
+            let axis = Axis(normalize_axis(axis, f.ndim(), vm)?);
+            let n = f.len_of(axis);
+            if let Some(x) = x.filter(|x| x.len() != n) {
+                return Err(vm.new_value_error(format!(
+                    "{} sample positions were given, but f has {n} along axis {}",
+                    x.len(),
+                    axis.index(),
+                )));
+            }
+            if n < 2 {
+                return Err(vm.new_value_error(format!(
+                    "A gradient needs at least 2 samples, but f has {n} along axis {}",
+                    axis.index(),
+                )));
+            }
+
+            // The distance from sample i to the next
+            let step = |i: usize| x.map_or(dx, |x| x[i + 1] - x[i]);
 
-    pub fn shape(&self) -> Vec<usize> {
-        self.read(|sliced| sliced.shape().to_vec())
+            let mut out = ArrayD::from_elem(f.raw_dim(), T::zero());
+            Zip::from(out.lanes_mut(axis))
+                .and(f.lanes(axis))
+                .for_each(|mut out, f| {
+                    out[0] = (f[1] - f[0]) / step(0);
+                    out[n - 1] = (f[n - 1] - f[n - 2]) / step(n - 2);
+                    for i in 1..n - 1 {
+                        let (before, after) = (step(i - 1), step(i));
+                        let span = before + after;
+                        out[i] = -after / (before * span) * f[i - 1]
+                            + (after - before) / (before * after) * f[i]
+                            + before / (after * span) * f[i + 1];
+                    }
+                });
+            Ok(out)
+        })
     }
+}
 
-    pub fn length(&self) -> usize {
-        self.read(|sliced| sliced.shape().get(0).copied().unwrap_or(1))
+/// Copies out the sample positions for integration, which must be 1-D
+fn sample_positions<T: Copy>(
+    x: Option<&SlicedArcArray<T>>,
+    vm: &VirtualMachine,
+) -> PyResult<Option<Vec<T>>> {
+    match x {
+        Some(x) if x.ndim() != 1 => Err(vm.new_value_error("x must be 1-dimensional".into())),
+        Some(x) => Ok(Some(x.read(|x| x.iter().copied().collect()))),
+        None => Ok(None),
     }
 }
 
-impl<T: Clone> SlicedArcArray<T> {
-    pub fn sliced_copy(&self) -> Self {
-        self.read(|sliced| Self::from_array(sliced.to_owned()))
+fn check_sample_count<T>(
+    x: Option<&[T]>,
+    n: usize,
+    axis: Axis,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    match x {
+        Some(x) if x.len() != n => Err(vm.new_value_error(format!(
+            "x has {} samples, but y has {n} along axis {}",
+            x.len(),
+            axis.index(),
+        ))),
+        _ => Ok(()),
     }
 }
 
-impl<T: Display> SlicedArcArray<T>
+/// Area of the trapezoid between samples `i - 1` and `i`
+fn trapezoid<T: Float>(lane: &ArrayView1<'_, T>, x: Option<&[T]>, dx: T, i: usize) -> T {
+    let width = x.map_or(dx, |x| x[i] - x[i - 1]);
+    width * (lane[i - 1] + lane[i]) / (T::one() + T::one())
+}
+
+/// Mean of the elements paired with true, as a reduction's `where=` mask picks them, as a
+/// scalar or reduced along `axis`. Picking nothing gives NaN.
+pub fn masked_mean<T>(
+    pairs: &SlicedArcArray<(T, bool)>,
+    axis: Option<isize>,
+    vm: &VirtualMachine,
+) -> PyResult
 where
+    T: Float + FromPrimitive + ToPyObject,
     SlicedArcArray<T>: GenericArray,
 {
-    pub fn repr(&self) -> String {
-        format!("array({}, dtype='{}')", self, Self::DTYPE.stringy_key())
-    }
-}
+    let mean = |iter: &mut dyn Iterator<Item = (T, bool)>| {
+        let (sum, count) = iter
+            .filter(|&(_, keep)| keep)
+            .fold((T::zero(), 0), |(sum, count), (x, _)| (sum + x, count + 1));
+        sum / T::from_usize(count).expect("Float from usize")
+    };
 
-impl<T: ToPyObject + Copy> SlicedArcArray<T> {
-    /// getitem, as implemented in the rustpython interface
-    pub fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult
-    where
-        SlicedArcArray<T>: GenericArray,
-    {
-        let slice = py_index_to_sliceinfo(needle, vm)?;
-        let sliced_self = self.append_slice(slice, vm)?;
+    pairs.read(|sliced| match axis {
+        None => Ok(mean(&mut sliced.iter().copied()).to_pyobject(vm)),
+        Some(axis) => {
+            let axis = Axis(normalize_axis(axis, sliced.ndim(), vm)?);
+            let means = sliced.map_axis(axis, |lane| mean(&mut lane.iter().copied()));
+            Ok(SlicedArcArray::from_array(means).cast().to_pyobject(vm))
+        }
+    })
+}
 
-        sliced_self.read(|sliced_array| {
-            if sliced_array.ndim() == 0 {
-                Ok(sliced_array.get([]).copied().unwrap().to_pyobject(vm))
-            } else {
-                Ok(sliced_self.cast().to_pyobject(vm))
-            }
-        })
+/// Unwraps a 0-D result into a Python scalar, as numpy does for full reductions
+pub fn scalar_or_array<T: Clone + ToPyObject>(arr: ArrayD<T>, vm: &VirtualMachine) -> PyObjectRef
+where
+    SlicedArcArray<T>: GenericArray,
+{
+    if arr.ndim() == 0 {
+        arr.first().cloned().expect("0-D arrays have one element").to_pyobject(vm)
+    } else {
+        SlicedArcArray::from_array(arr).cast().to_pyobject(vm)
     }
 }
 
@@ -141,6 +1524,13 @@ where
     }
     */
 
+    /// Assigns `other` into `slice` of us through `f`, once their shapes are checked to match.
+    ///
+    /// The locks are taken so as never to deadlock: `RwLock` isn't reentrant, so a thread
+    /// holding a read lock on some data must not then wait for the write lock on the same data.
+    /// When `other` shares our data (as in `a[1:] = a[:-1]`), its region is copied out, and the
+    /// read lock released, before the write lock is taken. Only when the data is separate is
+    /// the read lock taken while holding the write lock.
     pub fn assign_fn<F, U>(
         &self,
         slice: DynamicSlice,
@@ -151,50 +1541,612 @@ where
     where
         F: Fn(ArrayViewMutD<'_, T>, ArrayViewD<'_, T>, &VirtualMachine) -> PyResult<U>,
     {
-        // Check if we're copying from a slice of ourself ...
-        if Arc::ptr_eq(&self.unsliced, &other.unsliced) {
-            // TODO: THIS IS MEMORY INTENSIVE AND SLOW!!
-            let copied = other.read(|us| us.to_owned());
-            self.append_slice(slice, vm)?.write(|other_us| {
-                if other_us.shape() != copied.shape() {
-                    return Err(vm.new_runtime_error(format!(
-                        "Attempted to assign shape {:?} to shape {:?}",
-                        copied.shape(),
-                        other_us.shape(),
-                    )));
-                }
+        let dest = self.append_slice(slice, vm)?;
 
-                f(other_us, copied.view(), vm)
-            })
+        if self.shares_data_with(&other) {
+            // `read` applies the source's slices first, so this only copies the region being
+            // assigned from, never the whole backing array
+            let copied = other.read(|sliced| sliced.to_owned());
+            dest.write(|us| assign_checked(us, copied.view(), vm, &f))
         } else {
-            self.append_slice(slice, vm)?.write(|mut us| {
-                other.read(|them| {
-                    if us.shape() != them.shape() {
-                        return Err(vm.new_runtime_error(format!(
-                            "Attempted to assign shape {:?} to shape {:?}",
-                            them.shape(),
-                            us.shape(),
-                        )));
-                    }
+            dest.write(|mut us| other.read(|them| assign_checked(us.view_mut(), them, vm, &f)))
+        }
+    }
+}
 
-                    f(us.view_mut(), them.view(), vm)
-                })
+/// Runs the assignment `f` of `them` into `us`, which must be the same shape
+fn assign_checked<T, U>(
+    us: ArrayViewMutD<'_, T>,
+    them: ArrayViewD<'_, T>,
+    vm: &VirtualMachine,
+    f: &impl Fn(ArrayViewMutD<'_, T>, ArrayViewD<'_, T>, &VirtualMachine) -> PyResult<U>,
+) -> PyResult<U> {
+    if us.shape() != them.shape() {
+        return Err(vm.new_value_error(format!(
+            "Attempted to assign shape {:?} to shape {:?}",
+            them.shape(),
+            us.shape(),
+        )));
+    }
+
+    f(us, them, vm)
+}
+
+impl<T: PyRepr> Display for SlicedArcArray<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.read(|sliced| format_array(sliced, 0)))
+    }
+}
+
+/// The shift of `roll` as a whole number of places forward, within `0..len`
+fn roll_offset(shift: isize, len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        shift.rem_euclid(len as isize) as usize
+    }
+}
+
+/// How arrays are printed, as `set_printoptions` changes it
+#[derive(Clone, Debug)]
+pub struct PrintOptions {
+    /// Places after the point for floats, or None for as many as Python's repr gives
+    pub precision: Option<usize>,
+    /// Arrays of at least this many elements are printed summarized
+    pub threshold: usize,
+    /// Elements kept at each end of every axis of a summarized array
+    pub edgeitems: usize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            threshold: 1000,
+            edgeitems: 3,
+        }
+    }
+}
+
+/// The options every array is printed with. Like numpy's, they're shared by the whole process.
+pub static PRINT_OPTIONS: LazyLock<Mutex<PrintOptions>> =
+    LazyLock::new(|| Mutex::new(PrintOptions::default()));
+
+pub fn print_options() -> PrintOptions {
+    PRINT_OPTIONS.lock().unwrap().clone()
+}
+
+fn is_summarized<T>(view: &ArrayViewD<'_, T>, options: &PrintOptions) -> bool {
+    view.len() >= options.threshold
+}
+
+/// A shape as Python writes the tuple, such as `(3,)` or `(2, 3)`
+pub fn shape_repr(shape: &[usize]) -> String {
+    match shape {
+        [len] => format!("({len},)"),
+        shape => {
+            let lens: Vec<String> = shape.iter().map(|len| len.to_string()).collect();
+            format!("({})", lens.join(", "))
+        }
+    }
+}
+
+/// Formats an array as nested lists, or a 0-D array as its bare element. Rows after the first
+/// are indented by `indent` extra spaces, to line up with whatever is printed before the array.
+/// Big arrays are summarized as numpy does, eliding the middle of each axis with `...`.
+pub fn format_array<T: PyRepr>(view: ArrayViewD<'_, T>, indent: usize) -> String {
+    let mut out = String::new();
+    let options = print_options();
+    let summarize = is_summarized(&view, &options);
+    write_nested(&mut out, view, indent, summarize, &options);
+    out
+}
+
+fn write_nested<T: PyRepr>(
+    out: &mut String,
+    view: ArrayViewD<'_, T>,
+    indent: usize,
+    summarize: bool,
+    options: &PrintOptions,
+) {
+    if view.ndim() == 0 {
+        if let Some(elem) = view.iter().next() {
+            match options.precision {
+                Some(precision) => out.push_str(&elem.py_repr_rounded(precision)),
+                None => out.push_str(&elem.py_repr()),
+            }
+        }
+        return;
+    }
+
+    // Blocks of higher dimensional arrays get blank lines between them, as in numpy
+    let separator = match view.ndim() {
+        1 => ", ".to_string(),
+        ndim => format!(",{}{}", "\n".repeat(ndim - 1), " ".repeat(indent + 1)),
+    };
+
+    let len = view.len_of(Axis(0));
+    let edge = options.edgeitems;
+    let elided = if summarize && len > 2 * edge {
+        edge..len - edge
+    } else {
+        0..0
+    };
+
+    out.push('[');
+    for (i, sub) in view.outer_iter().enumerate() {
+        if elided.contains(&i) {
+            if i == elided.start {
+                out.push_str(&separator);
+                out.push_str("...");
+            }
+            continue;
+        }
+        if i > 0 {
+            out.push_str(&separator);
+        }
+        write_nested(out, sub, indent + 1, summarize, options);
+    }
+    out.push(']');
+}
+
+/// Unwraps each of `arrays` as the same dtype as `like`, raising on the first mismatch
+pub fn same_dtype_as<T>(
+    _like: &SlicedArcArray<T>,
+    arrays: Vec<AnyArray>,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<SlicedArcArray<T>>>
+where
+    SlicedArcArray<T>: GenericArray,
+{
+    arrays
+        .into_iter()
+        .map(|arr| {
+            let dtype = arr.dtype();
+            SlicedArcArray::<T>::from_any(arr).ok_or_else(|| {
+                vm.new_type_error(format!(
+                    "Expected dtype {}, got {}",
+                    SlicedArcArray::<T>::DTYPE.stringy_key(),
+                    dtype.stringy_key(),
+                ))
             })
+        })
+        .collect()
+}
+
+/// Matrix product, as numpy's `@` does it for 1-D and 2-D operands. 1-D operands act as a row
+/// (on the left) or column (on the right), and that axis is dropped from the result, so two
+/// vectors give a 0-D array.
+pub fn matmul<T: LinalgScalar>(
+    a: &SlicedArcArray<T>,
+    b: &SlicedArcArray<T>,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let a = a.read(|a| a.to_owned());
+    let b = b.read(|b| b.to_owned());
+
+    match (a.ndim(), b.ndim()) {
+        (1..=2, 1..=2) => {}
+        (0, _) | (_, 0) => {
+            return Err(vm.new_value_error("matmul: Operands must not be 0-dimensional".into()))
         }
+        _ => {
+            return Err(vm.new_value_error(
+                "matmul is only supported between 1-D and 2-D arrays".into(),
+            ))
+        }
+    }
+
+    let (a_matrix, b_matrix) = (as_matrix(&a, 0), as_matrix(&b, 1));
+
+    if a_matrix.ncols() != b_matrix.nrows() {
+        return Err(vm.new_value_error(format!(
+            "matmul: Shapes {:?} and {:?} are not aligned",
+            a.shape(),
+            b.shape()
+        )));
     }
+
+    let mut product = a_matrix.dot(&b_matrix).into_dyn();
+    if b.ndim() == 1 {
+        product = product.index_axis_move(Axis(1), 0);
+    }
+    if a.ndim() == 1 {
+        product = product.index_axis_move(Axis(0), 0);
+    }
+    Ok(product)
 }
 
-impl<T: Display> Display for SlicedArcArray<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.read(|slice| write!(f, "{slice}"))
+/// Views a 1-D or 2-D array as a matrix, with `vector_axis` added to a 1-D array
+fn as_matrix<T>(arr: &ArrayD<T>, vector_axis: usize) -> ArrayView2<'_, T> {
+    let view = if arr.ndim() == 1 {
+        arr.view().insert_axis(Axis(vector_axis))
+    } else {
+        arr.view()
+    };
+    view.into_dimensionality::<Ix2>().expect("Operands are 1-D or 2-D")
+}
+
+/// Every product of an element of `a` with one of `b`, as a 2-D array. Both are flattened first.
+pub fn outer<T: LinalgScalar>(a: &SlicedArcArray<T>, b: &SlicedArcArray<T>) -> ArrayD<T> {
+    let a: Vec<T> = a.read(|a| a.iter().copied().collect());
+    let b: Vec<T> = b.read(|b| b.iter().copied().collect());
+    ArrayD::from_shape_fn(IxDyn(&[a.len(), b.len()]), |i| a[i[0]] * b[i[1]])
+}
+
+/// Sums of products over the last axes of `a` and `b`, for every pair of indices along their
+/// other axes. Two vectors give a 0-D array.
+pub fn inner<T: LinalgScalar>(
+    a: &SlicedArcArray<T>,
+    b: &SlicedArcArray<T>,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let (a_shape, a) = a.read(|a| (a.shape().to_vec(), a.iter().copied().collect::<Vec<T>>()));
+    let (b_shape, b) = b.read(|b| (b.shape().to_vec(), b.iter().copied().collect::<Vec<T>>()));
+
+    let (Some((&len, a_outer)), Some((&b_len, b_outer))) =
+        (a_shape.split_last(), b_shape.split_last())
+    else {
+        return Err(vm.new_value_error("inner: Operands must not be 0-dimensional".into()));
+    };
+    if len != b_len {
+        return Err(vm.new_value_error(format!(
+            "inner: Shapes {a_shape:?} and {b_shape:?} differ along their last axes"
+        )));
+    }
+
+    // Each of the other indices is one row of a matrix
+    let a_rows: usize = a_outer.iter().product();
+    let b_rows: usize = b_outer.iter().product();
+    let a = Array2::from_shape_vec((a_rows, len), a).expect("Shape matches");
+    let b = Array2::from_shape_vec((b_rows, len), b).expect("Shape matches");
+    let product = a.dot(&b.t());
+
+    let shape: Vec<usize> = a_outer.iter().chain(b_outer).copied().collect();
+    Ok(ArrayD::from_shape_vec(shape, product.iter().copied().collect()).expect("Shape matches"))
+}
+
+/// Cross products of the 3-vectors along the last axes of `a` and `b`, broadcasting the
+/// leading axes against each other
+pub fn cross<T: LinalgScalar>(
+    a: &SlicedArcArray<T>,
+    b: &SlicedArcArray<T>,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    // Copied out, as a and b may well be views of the same array
+    let a = a.read(|a| a.to_owned());
+    let b = b.read(|b| b.to_owned());
+    if a.shape().last() != Some(&3) || b.shape().last() != Some(&3) {
+        return Err(vm.new_value_error(format!(
+            "cross: Shapes {:?} and {:?} must both have a last axis of length 3",
+            a.shape(),
+            b.shape()
+        )));
+    }
+
+    let shape = broadcast_shape(&[a.shape(), b.shape()], vm)?;
+    let a = broadcast_view(&a, &shape, vm)?;
+    let b = broadcast_view(&b, &shape, vm)?;
+    let axis = Axis(shape.len() - 1);
+    let mut product = a.to_owned();
+    Zip::from(product.lanes_mut(axis))
+        .and(a.lanes(axis))
+        .and(b.lanes(axis))
+        .for_each(|mut out, u, v| {
+            out[0] = u[1] * v[2] - u[2] * v[1];
+            out[1] = u[2] * v[0] - u[0] * v[2];
+            out[2] = u[0] * v[1] - u[1] * v[0];
+        });
+    Ok(product)
+}
+
+/// Takes `obj` as an array of dtype T, turning a scalar into a 0-D array that broadcasts
+/// against anything
+pub fn array_or_scalar<T: TryFromObject>(
+    obj: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<SlicedArcArray<T>>
+where
+    SlicedArcArray<T>: GenericArray,
+{
+    match AnyArray::try_from_object(vm, obj.clone()) {
+        Ok(arr) => {
+            let dtype = arr.dtype();
+            SlicedArcArray::<T>::from_any(arr).ok_or_else(|| {
+                vm.new_type_error(format!(
+                    "Expected dtype {}, got {}",
+                    SlicedArcArray::<T>::DTYPE.stringy_key(),
+                    dtype.stringy_key(),
+                ))
+            })
+        }
+        Err(_) => {
+            let value = T::try_from_object(vm, obj)?;
+            Ok(SlicedArcArray::from_array(ArrayD::from_elem(IxDyn(&[]), value)))
+        }
+    }
+}
+
+/// Shape that all of `shapes` broadcast to together, following numpy's rules
+pub fn broadcast_shape(shapes: &[&[usize]], vm: &VirtualMachine) -> PyResult<Vec<usize>> {
+    let ndim = shapes.iter().map(|shape| shape.len()).max().unwrap_or(0);
+    let mut out = vec![1; ndim];
+
+    for shape in shapes {
+        // Shapes are aligned on their trailing axes
+        for (out_len, &len) in out.iter_mut().rev().zip(shape.iter().rev()) {
+            if *out_len == 1 {
+                *out_len = len;
+            } else if len != 1 && len != *out_len {
+                return Err(vm.new_value_error(format!(
+                    "Operands could not be broadcast together with shapes {shapes:?}"
+                )));
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Picks from `x` where `cond` holds and from `y` elsewhere, broadcasting all three together
+pub fn where_<T: Clone>(
+    cond: &ArrayD<bool>,
+    x: &SlicedArcArray<T>,
+    y: &SlicedArcArray<T>,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    // Copied out, as x and y may well be views of the same array
+    let x = x.read(|x| x.to_owned());
+    let y = y.read(|y| y.to_owned());
+    let shape = broadcast_shape(&[cond.shape(), x.shape(), y.shape()], vm)?;
+
+    Ok(Zip::from(broadcast_view(cond, &shape, vm)?)
+        .and(broadcast_view(&x, &shape, vm)?)
+        .and(broadcast_view(&y, &shape, vm)?)
+        .map_collect(|&c, x, y| if c { x.clone() } else { y.clone() }))
+}
+
+/// `f` applied elementwise over `a` and `b` broadcast together
+pub fn broadcast_zip<A: Clone, B: Clone, U>(
+    a: &SlicedArcArray<A>,
+    b: &SlicedArcArray<B>,
+    vm: &VirtualMachine,
+    f: impl Fn(A, B) -> U,
+) -> PyResult<ArrayD<U>> {
+    // Copied out, as a and b may well be views of the same array
+    let a = a.read(|a| a.to_owned());
+    let b = b.read(|b| b.to_owned());
+    let shape = broadcast_shape(&[a.shape(), b.shape()], vm)?;
+
+    Ok(Zip::from(broadcast_view(&a, &shape, vm)?)
+        .and(broadcast_view(&b, &shape, vm)?)
+        .map_collect(|a, b| f(a.clone(), b.clone())))
+}
+
+fn broadcast_view<'a, T>(
+    arr: &'a ArrayD<T>,
+    shape: &[usize],
+    vm: &VirtualMachine,
+) -> PyResult<ArrayViewD<'a, T>> {
+    arr.broadcast(shape).ok_or_else(|| {
+        vm.new_value_error(format!(
+            "Could not broadcast shape {:?} to {shape:?}",
+            arr.shape()
+        ))
+    })
+}
+
+/// Joins `arrays` along an existing axis
+pub fn concatenate<T: Clone>(
+    arrays: &[SlicedArcArray<T>],
+    axis: isize,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let owned: Vec<ArrayD<T>> = arrays
+        .iter()
+        .map(|arr| arr.read(|sliced| sliced.to_owned()))
+        .collect();
+
+    concatenate_owned(owned, axis, vm)
+}
+
+/// Joins `arrays` row-wise, treating 0-D and 1-D arrays as single rows
+pub fn vstack<T: Clone>(arrays: &[SlicedArcArray<T>], vm: &VirtualMachine) -> PyResult<ArrayD<T>> {
+    let rows: Vec<ArrayD<T>> = arrays
+        .iter()
+        .map(|arr| {
+            arr.read(|sliced| {
+                let mut owned = sliced.to_owned();
+                while owned.ndim() < 2 {
+                    owned.insert_axis_inplace(Axis(0));
+                }
+                owned
+            })
+        })
+        .collect();
+
+    concatenate_owned(rows, 0, vm)
+}
+
+/// Joins `arrays` column-wise, or end to end if they are 1-D
+pub fn hstack<T: Clone>(arrays: &[SlicedArcArray<T>], vm: &VirtualMachine) -> PyResult<ArrayD<T>> {
+    let owned: Vec<ArrayD<T>> = arrays
+        .iter()
+        .map(|arr| {
+            arr.read(|sliced| {
+                let mut owned = sliced.to_owned();
+                if owned.ndim() == 0 {
+                    owned.insert_axis_inplace(Axis(0));
+                }
+                owned
+            })
+        })
+        .collect();
+
+    let axis = match owned.first() {
+        Some(arr) if arr.ndim() > 1 => 1,
+        _ => 0,
+    };
+
+    concatenate_owned(owned, axis, vm)
+}
+
+fn concatenate_owned<T: Clone>(
+    owned: Vec<ArrayD<T>>,
+    axis: isize,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let first = owned
+        .first()
+        .ok_or_else(|| vm.new_value_error("Need at least one array to concatenate".into()))?;
+    let axis = normalize_axis(axis, first.ndim(), vm)?;
+
+    for arr in &owned {
+        let matches = arr.ndim() == first.ndim()
+            && (0..first.ndim())
+                .filter(|&i| i != axis)
+                .all(|i| arr.shape()[i] == first.shape()[i]);
+
+        if !matches {
+            return Err(vm.new_value_error(format!(
+                "Cannot concatenate shape {:?} with shape {:?} along axis {axis}",
+                arr.shape(),
+                first.shape(),
+            )));
+        }
+    }
+
+    let views: Vec<ArrayViewD<'_, T>> = owned.iter().map(|arr| arr.view()).collect();
+    ndarray::concatenate(Axis(axis), &views).map_err(|e| vm.new_value_error(format!("{e}")))
+}
+
+/// Joins `arrays`, which must all share a shape, along a new axis
+pub fn stack<T: Clone>(
+    arrays: &[SlicedArcArray<T>],
+    axis: isize,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let owned: Vec<ArrayD<T>> = arrays
+        .iter()
+        .map(|arr| arr.read(|sliced| sliced.to_owned()))
+        .collect();
+
+    let first = owned
+        .first()
+        .ok_or_else(|| vm.new_value_error("Need at least one array to stack".into()))?;
+    let axis = normalize_axis(axis, first.ndim() + 1, vm)?;
+
+    if let Some(arr) = owned.iter().find(|arr| arr.shape() != first.shape()) {
+        return Err(vm.new_value_error(format!(
+            "All arrays must have the same shape to stack; got {:?} and {:?}",
+            first.shape(),
+            arr.shape(),
+        )));
+    }
+
+    let views: Vec<ArrayViewD<'_, T>> = owned.iter().map(|arr| arr.view()).collect();
+    ndarray::stack(Axis(axis), &views).map_err(|e| vm.new_value_error(format!("{e}")))
+}
+
+/// Converts a possibly negative axis into one in `0..ndim`
+pub fn normalize_axis(axis: isize, ndim: usize, vm: &VirtualMachine) -> PyResult<usize> {
+    let normalized = if axis < 0 { axis + ndim as isize } else { axis };
+
+    if normalized < 0 || normalized >= ndim as isize {
+        return Err(vm.new_value_error(format!(
+            "Axis {axis} is out of bounds for array of dimension {ndim}"
+        )));
+    }
+
+    Ok(normalized as usize)
+}
+
+/// Puts negative integer indices in `slice` in terms of the length of their axis in `shape`, as
+/// Python counts them back from the end, raising IndexError for any index out of bounds. Slice
+/// bounds are clamped to the axis as Python does, so that they give an empty range rather than
+/// going out of bounds, and slices with a negative step are turned from Python's `start` and
+/// `stop` into the forward range ndarray walks from the back.
+pub fn normalize_indices(
+    slice: &DynamicSlice,
+    shape: &[usize],
+    vm: &VirtualMachine,
+) -> PyResult<DynamicSlice> {
+    let mut axis = 0;
+    let mut elems = Vec::with_capacity(slice.len());
+
+    for &elem in slice.iter() {
+        match elem {
+            SliceInfoElem::NewAxis => {
+                elems.push(elem);
+                continue;
+            }
+            // Indices beyond the last axis are left for the bounds check to report
+            SliceInfoElem::Index(index) if axis < shape.len() => {
+                let normalized = normalize_index(index, shape[axis], axis, vm)?;
+                elems.push(SliceInfoElem::Index(normalized as isize));
+            }
+            SliceInfoElem::Slice { start, end, step } if axis < shape.len() => {
+                let len = shape[axis] as isize;
+                let (start, end) = if step > 0 {
+                    let clamp = |bound: isize| {
+                        if bound < 0 {
+                            (bound + len).max(0)
+                        } else {
+                            bound.min(len)
+                        }
+                    };
+                    let start = clamp(start);
+                    // A slice ending before it starts is empty
+                    (start, end.map(|end| clamp(end).max(start)))
+                } else {
+                    // Python walks down from `start` to just above `end`, so the range is
+                    // `end + 1 ..= start`, with -1 standing for before the first element
+                    let clamp = |bound: isize| {
+                        if bound < 0 {
+                            (bound + len).max(-1)
+                        } else {
+                            bound.min(len - 1)
+                        }
+                    };
+                    let first = clamp(start);
+                    let last = end.map_or(-1, clamp);
+                    // A slice ending before it starts is empty
+                    (last + 1, Some(first.max(last) + 1))
+                };
+                elems.push(SliceInfoElem::Slice { start, end, step });
+            }
+            _ => elems.push(elem),
+        }
+        axis += 1;
     }
+
+    Ok(DynamicSlice::try_from(elems).expect("Same number of axes"))
+}
+
+/// `index` into `axis` of length `len`, counting back from the end if negative
+pub fn normalize_index(
+    index: isize,
+    len: usize,
+    axis: usize,
+    vm: &VirtualMachine,
+) -> PyResult<usize> {
+    let normalized = if index < 0 { index + len as isize } else { index };
+    usize::try_from(normalized)
+        .ok()
+        .filter(|&normalized| normalized < len)
+        .ok_or_else(|| {
+            vm.new_index_error(format!(
+                "Index {index} is out of bounds for axis {axis} with size {len}"
+            ))
+        })
 }
 
-/// Converts a PyInt to an isize
+/// Converts a PyInt to an isize, for indexing. Anything too big to be one can only be out of
+/// bounds.
 pub fn pyint_to_isize(int: &PyInt, vm: &VirtualMachine) -> PyResult<isize> {
     int.as_bigint()
         .try_into()
-        .map_err(|e| vm.new_runtime_error(format!("Bigint cast {e}")))
+        .map_err(|_| vm.new_index_error(format!("Index {} is out of bounds", int.as_bigint())))
 }
 
 /// Converts a PyObject to an isize
@@ -205,7 +2157,12 @@ pub fn py_obj_elem_to_isize(obj: &PyObject, vm: &VirtualMachine) -> PyResult<Opt
 
     let int: &PyInt = obj
         .downcast_ref::<PyInt>()
-        .ok_or_else(|| vm.new_runtime_error("Indices must be isize".to_string()))?;
+        .ok_or_else(|| {
+            vm.new_type_error(format!(
+                "Slice indices must be integers or None, not {}",
+                obj.class().name()
+            ))
+        })?;
 
     pyint_to_isize(int, vm).map(Some)
 }
@@ -231,9 +2188,15 @@ pub fn py_index_elem_to_sliceinfo_elem(
             .as_ref()
             .and_then(|step| py_obj_elem_to_isize(step, vm).transpose())
             .transpose()?;
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return Err(vm.new_value_error("slice step cannot be zero".to_owned()));
+        }
+        // Still Python's bounds, which `normalize_indices` turns into ndarray's. Going
+        // backwards, the default start is the last element, which -1 also is.
         return Ok(SliceInfoElem::Slice {
-            start: start.unwrap_or(0),
-            step: step.unwrap_or(1),
+            start: start.unwrap_or(if step < 0 { -1 } else { 0 }),
+            step,
             end: stop,
         });
     }
@@ -242,24 +2205,186 @@ pub fn py_index_elem_to_sliceinfo_elem(
         return Ok(SliceInfoElem::NewAxis);
     }
 
-    Err(vm.new_runtime_error(format!("Unrecognized index {elem:?}")))
+    Err(vm.new_index_error(format!(
+        "Only integers, slices, ellipsis ('...') and None are valid indices, not {}",
+        elem.class().name()
+    )))
 }
 
-/// Converts a PyObject to a DynamicSlice
-pub fn py_index_to_sliceinfo(shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<DynamicSlice> {
-    if let Ok(single) = py_index_elem_to_sliceinfo_elem(shape.clone(), vm) {
-        return Ok(DynamicSlice::try_from(vec![single]).unwrap());
+/// Converts a PyObject index into a DynamicSlice for an array with `ndim` axes. An Ellipsis
+/// expands to as many full slices as needed, and any axes left over are taken whole.
+pub fn py_index_to_sliceinfo(
+    needle: PyObjectRef,
+    ndim: usize,
+    vm: &VirtualMachine,
+) -> PyResult<DynamicSlice> {
+    let members: Vec<PyObjectRef> = match needle.downcast_ref::<PyTuple>() {
+        Some(tuple) => tuple.iter().cloned().collect(),
+        None => vec![needle],
+    };
+
+    let mut before_ellipsis = vec![];
+    let mut after_ellipsis = vec![];
+    let mut seen_ellipsis = false;
+
+    for member in members {
+        if member.is(&vm.ctx.ellipsis) {
+            if seen_ellipsis {
+                return Err(vm.new_index_error(
+                    "An index can only have a single ellipsis ('...')".into(),
+                ));
+            }
+            seen_ellipsis = true;
+            continue;
+        }
+
+        let elem = py_index_elem_to_sliceinfo_elem(member, vm)?;
+        if seen_ellipsis {
+            after_ellipsis.push(elem);
+        } else {
+            before_ellipsis.push(elem);
+        }
     }
 
-    if let Some(tuple) = shape.downcast_ref::<PyTuple>() {
-        let indices: Vec<SliceInfoElem> = tuple
-            .iter()
-            .map(|member| py_index_elem_to_sliceinfo_elem(member.clone(), vm))
-            .collect::<PyResult<_>>()?;
-        return Ok(DynamicSlice::try_from(indices).unwrap());
+    let indexed = before_ellipsis
+        .iter()
+        .chain(&after_ellipsis)
+        .filter(|elem| !matches!(elem, SliceInfoElem::NewAxis))
+        .count();
+
+    if indexed > ndim {
+        return Err(vm.new_index_error(format!(
+            "Too many indices for array: array is {ndim}-dimensional, but {indexed} were indexed"
+        )));
+    }
+
+    let mut indices = before_ellipsis;
+    indices.extend(std::iter::repeat(SliceInfoElem::from(..)).take(ndim - indexed));
+    indices.extend(after_ellipsis);
+
+    Ok(DynamicSlice::try_from(indices).unwrap())
+}
+
+fn index_has_ellipsis(needle: &PyObject, vm: &VirtualMachine) -> bool {
+    match needle.downcast_ref::<PyTuple>() {
+        Some(tuple) => tuple.iter().any(|member| member.is(&vm.ctx.ellipsis)),
+        None => needle.is(&vm.ctx.ellipsis),
+    }
+}
+
+/// Converts a (possibly nested) Python list or tuple into an array, inferring its shape from
+/// the nesting. Anything else is taken to be a scalar, and becomes a 0-D array.
+pub fn py_nested_list_to_array<T: TryFromObject>(
+    data: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    // Follow the first member at each level down to find the shape ...
+    let mut shape = vec![];
+    let mut first = data.clone();
+    while let Some(members) = py_sequence_members(&first) {
+        shape.push(members.len());
+        match members.into_iter().next() {
+            Some(member) => first = member,
+            None => break,
+        }
+    }
+
+    // ... then check everything else agrees with it while flattening
+    let mut flat = vec![];
+    flatten_nested_list(data, &shape, &mut flat, vm)?;
+
+    ArrayD::from_shape_vec(shape, flat).map_err(|e| vm.new_value_error(format!("{e}")))
+}
+
+fn flatten_nested_list<T: TryFromObject>(
+    obj: PyObjectRef,
+    shape: &[usize],
+    flat: &mut Vec<T>,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    match (py_sequence_members(&obj), shape.split_first()) {
+        (Some(members), Some((&len, rest))) if members.len() == len => {
+            for member in members {
+                flatten_nested_list(member, rest, flat, vm)?;
+            }
+            Ok(())
+        }
+        (None, None) => {
+            flat.push(T::try_from_object(vm, obj)?);
+            Ok(())
+        }
+        _ => Err(vm.new_value_error(
+            "Cannot build an array from a ragged nested sequence".into(),
+        )),
+    }
+}
+
+/// The members of a list or tuple, or None for anything else
+fn py_sequence_members(obj: &PyObject) -> Option<Vec<PyObjectRef>> {
+    if let Some(list) = obj.downcast_ref::<PyList>() {
+        return Some(list.borrow_vec().to_vec());
+    }
+
+    if let Some(tuple) = obj.downcast_ref::<PyTuple>() {
+        return Some(tuple.as_slice().to_vec());
+    }
+
+    None
+}
+
+/// Parses `pad`'s widths into `(before, after)` for each of `ndim` axes. One int pads every
+/// side alike, one pair pads every axis alike, or there may be a pair for each axis.
+pub fn py_pad_width_to_rust(
+    pad_width: PyObjectRef,
+    ndim: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<(usize, usize)>> {
+    let width = |obj: PyObjectRef| -> PyResult<usize> {
+        let width = isize::try_from_object(vm, obj)?;
+        usize::try_from(width)
+            .map_err(|_| vm.new_value_error(format!("Pad widths can't be negative, got {width}")))
+    };
+    let pair = |obj: PyObjectRef| -> PyResult<(usize, usize)> {
+        let Some(members) = py_sequence_members(&obj) else {
+            let both = width(obj)?;
+            return Ok((both, both));
+        };
+        match members.as_slice() {
+            [both] => {
+                let both = width(both.clone())?;
+                Ok((both, both))
+            }
+            [before, after] => Ok((width(before.clone())?, width(after.clone())?)),
+            _ => Err(vm.new_value_error(format!(
+                "Expected a (before, after) pair of pad widths, got {} widths",
+                members.len()
+            ))),
+        }
+    };
+
+    match py_sequence_members(&pad_width) {
+        Some(pairs) if pairs.iter().any(|pair| py_sequence_members(pair).is_some()) => {
+            if pairs.len() != ndim {
+                return Err(vm.new_value_error(format!(
+                    "Expected pad widths for {ndim} axes, got {}",
+                    pairs.len()
+                )));
+            }
+            pairs.into_iter().map(&pair).collect()
+        }
+        _ => Ok(vec![pair(pad_width)?; ndim]),
     }
+}
 
-    Err(vm.new_runtime_error(format!("Unrecognized sliceinfo index {shape:?}")))
+/// Parses the shape passed to `reshape`, which may have a -1 length to infer
+pub fn py_new_shape_to_rust(shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<isize>> {
+    match py_sequence_members(&shape) {
+        Some(members) => members
+            .into_iter()
+            .map(|len| isize::try_from_object(vm, len))
+            .collect(),
+        None => Ok(vec![isize::try_from_object(vm, shape)?]),
+    }
 }
 
 /// Converts a PyObject shape to a Vec<usize>
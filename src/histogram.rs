@@ -0,0 +1,45 @@
+/// `bins + 1` evenly spaced edges from `lo` to `hi`. Equal ends are first widened by a half
+/// either way, as in numpy, so that there's still a width to divide.
+pub fn uniform_edges(lo: f64, hi: f64, bins: usize) -> Vec<f64> {
+    let (lo, hi) = if lo == hi { (lo - 0.5, hi + 0.5) } else { (lo, hi) };
+    let step = (hi - lo) / bins as f64;
+    let mut edges: Vec<f64> = (0..=bins).map(|i| lo + step * i as f64).collect();
+    // Rounding mustn't leave the largest sample out of the last bin
+    edges[bins] = hi;
+    edges
+}
+
+/// Which bin `x` falls in. Each bin is `[edges[i], edges[i + 1])`, except that the last also
+/// takes its right edge. None for samples outside the edges, and for NaN.
+pub fn bin_of(edges: &[f64], x: f64) -> Option<usize> {
+    let bins = edges.len().checked_sub(1).filter(|&bins| bins > 0)?;
+    if x == edges[bins] {
+        return Some(bins - 1);
+    }
+
+    // How many edges are at or below x, which is one past its bin
+    let below = edges.partition_point(|&edge| edge <= x);
+    (1..=bins).contains(&below).then(|| below - 1)
+}
+
+/// How many of `samples` fall in each bin between `edges`
+pub fn histogram(samples: &[f64], edges: &[f64]) -> Vec<i64> {
+    let mut counts = vec![0; edges.len().saturating_sub(1)];
+    for bin in samples.iter().filter_map(|&x| bin_of(edges, x)) {
+        counts[bin] += 1;
+    }
+    counts
+}
+
+/// How many of the points `(x[i], y[i])` fall in each bin, indexed by the `x` bin then the `y`
+/// bin. Points outside either set of edges aren't counted.
+pub fn histogram2d(x: &[f64], y: &[f64], x_edges: &[f64], y_edges: &[f64]) -> Vec<Vec<f64>> {
+    let y_bins = y_edges.len().saturating_sub(1);
+    let mut counts = vec![vec![0.0; y_bins]; x_edges.len().saturating_sub(1)];
+    for (&x, &y) in x.iter().zip(y) {
+        if let (Some(i), Some(j)) = (bin_of(x_edges, x), bin_of(y_edges, y)) {
+            counts[i][j] += 1.0;
+        }
+    }
+    counts
+}
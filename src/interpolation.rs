@@ -0,0 +1,39 @@
+/// Piecewise linear interpolation through the points `(xp[i], fp[i])`, with `xp` increasing
+/// and not empty. Beyond the first point gives `left` and beyond the last `right`.
+pub fn interp(x: f64, xp: &[f64], fp: &[f64], left: f64, right: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+
+    // The first point past x, so that x is in [xp[j - 1], xp[j])
+    let j = xp.partition_point(|&p| p <= x);
+    if j == 0 {
+        left
+    } else if j == xp.len() {
+        if x == xp[j - 1] {
+            fp[j - 1]
+        } else {
+            right
+        }
+    } else {
+        let slope = (fp[j] - fp[j - 1]) / (xp[j] - xp[j - 1]);
+        fp[j - 1] + slope * (x - xp[j - 1])
+    }
+}
+
+/// The points `(xp, fp)` of data repeating every `period`, brought into one period, sorted,
+/// and with a point copied from either end past the other, so that `interp` can wrap around
+pub fn periodic_points(xp: &[f64], fp: &[f64], period: f64) -> (Vec<f64>, Vec<f64>) {
+    let mut points: Vec<(f64, f64)> = xp
+        .iter()
+        .map(|x| x.rem_euclid(period))
+        .zip(fp.iter().copied())
+        .collect();
+    points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let wrapped = std::iter::once((last.0 - period, last.1))
+        .chain(points)
+        .chain(std::iter::once((first.0 + period, first.1)));
+    wrapped.unzip()
+}
@@ -1,6 +1,6 @@
 //#![allow(unused)]
 
-use ndarray::{ArrayViewD, ArrayViewMutD, SliceInfoElem};
+use ndarray::{ArrayViewD, ArrayViewMutD, Dimension, SliceInfoElem};
 use rustpython_vm::{
     atomic_func,
     builtins::{PyModule, PyStr},
@@ -12,15 +12,95 @@ use rustpython_vm::{
 };
 use std::sync::LazyLock;
 
+pub mod covariance;
+pub mod element;
 pub mod generic_pyndarray;
-use generic_pyndarray::{py_shape_to_rust, DynamicSlice, SlicedArcArray};
+pub mod histogram;
+pub mod interpolation;
+pub mod npy;
+pub mod polynomial;
+use element::Element;
+use generic_pyndarray::{
+    py_nested_list_to_array, py_new_shape_to_rust, py_pad_width_to_rust, py_shape_to_rust,
+    DynamicSlice, SlicedArcArray,
+};
+
+/// Evaluates `$body` with `$arr` bound to the `SlicedArcArray` inside an `AnyArray`, whatever
+/// its dtype. The body is expanded once per dtype, so it may be generic over the element.
+macro_rules! with_array {
+    ($any:expr, $arr:ident => $body:expr) => {
+        match $any {
+            $crate::AnyArray::Float32($arr) => $body,
+            $crate::AnyArray::Float64($arr) => $body,
+            $crate::AnyArray::Int32($arr) => $body,
+            $crate::AnyArray::Int64($arr) => $body,
+        }
+    };
+}
+
+/// As `with_array!`, but for operations which only make sense on floats. Integer arrays are
+/// converted to float64 first, as numpy does.
+macro_rules! with_float_array {
+    ($any:expr, $arr:ident => $body:expr) => {
+        match $any.into_float() {
+            $crate::AnyArray::Float32($arr) => $body,
+            $crate::AnyArray::Float64($arr) => $body,
+            _ => unreachable!("into_float gives a float dtype"),
+        }
+    };
+}
+
+/// Runs `$body` with `$t` naming the primitive type of `$dtype`, which must be numeric
+macro_rules! with_dtype {
+    ($dtype:expr, $t:ident => $body:expr) => {
+        match $dtype {
+            $crate::DataType::Float32 => {
+                type $t = f32;
+                $body
+            }
+            $crate::DataType::Float64 => {
+                type $t = f64;
+                $body
+            }
+            $crate::DataType::Int32 => {
+                type $t = i32;
+                $body
+            }
+            $crate::DataType::Int64 => {
+                type $t = i64;
+                $body
+            }
+            $crate::DataType::Bool => unreachable!("bool has no arithmetic, so is made separately"),
+        }
+    };
+}
+
+// Declared after the macros, which it uses
+pub mod linalg;
 
 pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let module = pyndarray::make_module(vm);
     //module.set_attr("PyNdArrayFloat32", pyndarray::PyNdArrayFloat32::make_class(&vm.ctx), vm);
-    pyndarray::PyNdArrayFloat32::make_class(&vm.ctx);
-    pyndarray::PyNdArrayFloat64::make_class(&vm.ctx);
+    let classes = [
+        pyndarray::PyNdArrayFloat32::make_class(&vm.ctx),
+        pyndarray::PyNdArrayFloat64::make_class(&vm.ctx),
+        pyndarray::PyNdArrayInt32::make_class(&vm.ctx),
+        pyndarray::PyNdArrayInt64::make_class(&vm.ctx),
+        pyndarray::PyNdArrayBool::make_class(&vm.ctx),
+    ];
+
+    // Kept on the classes for `__reduce__`, which then needn't know what name the host
+    // registered the module under to import it again
+    let reconstruct = module
+        .get_attr("_reconstruct", vm)
+        .expect("The module defines _reconstruct");
+    for class in classes {
+        class.set_attr(vm.ctx.intern_str("_reconstruct"), reconstruct.clone());
+    }
 
+    module
+        .set_attr("linalg", linalg::pylinalg::make_module(vm), vm)
+        .expect("Setting an attribute of a fresh module");
     module
 }
 
@@ -28,45 +108,141 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
 pub enum DataType {
     Float32,
     Float64,
+    Int32,
+    Int64,
+    Bool,
 }
 
 pub trait GenericArray {
     type PyArray: PyObjectPayload + ToPyObject;
     fn cast(&self) -> Self::PyArray;
     const DTYPE: DataType;
+    /// Unwraps `any` if it holds this dtype
+    fn from_any(any: AnyArray) -> Option<Self>
+    where
+        Self: Sized;
+}
+
+/// An array of any dtype, for functions which accept more than one
+#[derive(Clone, Debug)]
+pub enum AnyArray {
+    Float32(SlicedArcArray<f32>),
+    Float64(SlicedArcArray<f64>),
+    Int32(SlicedArcArray<i32>),
+    Int64(SlicedArcArray<i64>),
+}
+
+impl AnyArray {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            AnyArray::Float32(_) => DataType::Float32,
+            AnyArray::Float64(_) => DataType::Float64,
+            AnyArray::Int32(_) => DataType::Int32,
+            AnyArray::Int64(_) => DataType::Int64,
+        }
+    }
+
+    /// This array if it already has `dtype`, or else a copy converted to it, which must be
+    /// numeric. Converting to a narrower dtype rounds or truncates, as `astype` does.
+    pub fn into_dtype(self, dtype: DataType) -> Self {
+        if self.dtype() == dtype {
+            return self;
+        }
+        with_array!(self, arr => with_dtype!(dtype, T => AnyArray::from(arr.map(|x| x as T))))
+    }
+
+    /// This array if its dtype is a float, or else a float64 copy
+    pub fn into_float(self) -> Self {
+        match self {
+            AnyArray::Int32(arr) => AnyArray::Float64(arr.map(f64::from)),
+            AnyArray::Int64(arr) => AnyArray::Float64(arr.map(|x| x as f64)),
+            float => float,
+        }
+    }
 }
 
-#[rustpython_vm::pymodule]
+impl TryFromObject for AnyArray {
+    fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
+        if let Some(arr) = obj.downcast_ref::<pyndarray::PyNdArrayFloat32>() {
+            return Ok(AnyArray::Float32(arr.arr.clone()));
+        }
+
+        if let Some(arr) = obj.downcast_ref::<pyndarray::PyNdArrayFloat64>() {
+            return Ok(AnyArray::Float64(arr.arr.clone()));
+        }
+
+        if let Some(arr) = obj.downcast_ref::<pyndarray::PyNdArrayInt32>() {
+            return Ok(AnyArray::Int32(arr.arr.clone()));
+        }
+
+        if let Some(arr) = obj.downcast_ref::<pyndarray::PyNdArrayInt64>() {
+            return Ok(AnyArray::Int64(arr.arr.clone()));
+        }
+
+        Err(vm.new_type_error(format!(
+            "Expected an array, got {}",
+            obj.class().name()
+        )))
+    }
+}
+
+// Named as it's registered, so that pickles can find `_reconstruct` again
+#[rustpython_vm::pymodule(name = "ndarray")]
 pub mod pyndarray {
     use super::*;
-    use builtins::{PyFloat, PyInt, PyStrRef};
-    use function::{KwArgs, OptionalArg};
-    use generic_pyndarray::py_index_to_sliceinfo;
-    use rustpython_vm::types::{AsMapping, AsNumber, AsSequence};
+    use builtins::{
+        PyBaseExceptionRef, PyBytes, PyDict, PyInt, PyIntRef, PyList, PyStrRef, PyTuple,
+        PyTupleRef, PyType,
+    };
+    use class::StaticType;
+    use function::{
+        ArgBytesLike, ArgIntoFloat, ArgSequence, Either, KwArgs, OptionalArg, PosArgs,
+        PyComparisonValue,
+    };
+    use generic_pyndarray::{
+        array_or_scalar, broadcast_zip, flattened, flattened_in_order, is_in_order,
+        normalize_indices, py_index_to_sliceinfo, same_dtype_as, scalar_or_array, sorted_median,
+        sorted_quantile, to_owned_with_order, Order, PRINT_OPTIONS,
+    };
+    use num_traits::{AsPrimitive, NumCast, One, Zero};
+    use rustpython_vm::common::hash::PyHash;
+    use rustpython_vm::convert::IntoPyException;
+    use rustpython_vm::protocol::{BufferDescriptor, BufferMethods, PyBuffer};
+    use rustpython_vm::types::{AsBuffer, AsMapping, AsNumber, AsSequence, PyComparisonOp};
     use rustpython_vm::*;
 
     macro_rules! build_pyarray {
-        ($primitive:ident, $dtype:ident, $dtype_enum:expr) => {
+        ($primitive:ident, $dtype:ident, $variant:ident) => {
             #[derive(PyPayload, Clone, Debug)]
-            #[pyclass(module = "pyndarray", name)]
+            #[pyclass(module = "ndarray", name)]
             pub struct $dtype {
                 pub arr: SlicedArcArray<$primitive>,
             }
 
             impl GenericArray for SlicedArcArray<$primitive> {
                 type PyArray = $dtype;
-                const DTYPE: DataType = $dtype_enum;
+                const DTYPE: DataType = DataType::$variant;
                 fn cast(&self) -> Self::PyArray {
                     $dtype { arr: self.clone() }
                 }
+                fn from_any(any: AnyArray) -> Option<Self> {
+                    match any {
+                        AnyArray::$variant(arr) => Some(arr),
+                        _ => None,
+                    }
+                }
             }
 
             //#[pyclass]
-            #[pyclass(with(AsMapping, AsNumber, AsSequence))]
+            #[pyclass(with(AsBuffer, AsMapping, AsNumber, AsSequence))]
             impl $dtype {
                 // AsMapping methods
                 #[pymethod(magic)]
                 fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(mask) = needle.downcast_ref::<PyNdArrayBool>() {
+                        let picked = self.arr.masked(&mask.arr, vm)?;
+                        return Ok(SlicedArcArray::from_array(picked).cast().to_pyobject(vm));
+                    }
                     self.arr.getitem(needle, vm)
                 }
 
@@ -77,7 +253,7 @@ pub mod pyndarray {
                     value: PyObjectRef,
                     vm: &VirtualMachine,
                 ) -> PyResult<()> {
-                    let slice = py_index_to_sliceinfo(needle, vm)?;
+                    let slice = py_index_to_sliceinfo(needle, self.arr.ndim(), vm)?;
                     self.assign_or_elem_fn(
                         slice,
                         value,
@@ -87,12 +263,97 @@ pub mod pyndarray {
                     )
                 }
 
+                /// Changes the shape in place, dropping elements off the end of the flattened
+                /// array or padding it with zeros
+                #[pymethod]
+                fn resize(&self, shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                    let shape = py_shape_to_rust(shape, vm)?;
+                    self.arr.resize(shape, $primitive::zero(), vm)
+                }
+
+                /// Fills the whole (sliced) array in place with a scalar
+                #[pymethod]
+                fn fill(&self, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                    let value: $primitive =
+                        fill_element(value, SlicedArcArray::<$primitive>::DTYPE, vm)?;
+                    self.arr.fill(empty_slice_like(&self.arr), value, vm)
+                }
+
+                /// Whether any element equals `value`. Values which can't be an element, such as
+                /// 0.5 for an integer array, are never found.
+                #[pymethod(magic)]
+                fn contains(&self, value: PyObjectRef, vm: &VirtualMachine) -> bool {
+                    $primitive::try_from_object(vm, value)
+                        .is_ok_and(|value| self.arr.contains(value))
+                }
+
+                #[pyslot]
+                fn slot_hash(zelf: &PyObject, vm: &VirtualMachine) -> PyResult<PyHash> {
+                    unhashable(zelf, vm)
+                }
+
+                /// Compares elementwise, giving a bool array as numpy does. NaN compares unequal
+                /// to everything, itself included.
+                #[pyslot]
+                fn slot_richcompare(
+                    zelf: &PyObject,
+                    other: &PyObject,
+                    op: PyComparisonOp,
+                    vm: &VirtualMachine,
+                ) -> PyResult<Either<PyObjectRef, PyComparisonValue>> {
+                    let Some(zelf) = zelf.downcast_ref::<Self>() else {
+                        return Ok(Either::B(PyComparisonValue::NotImplemented));
+                    };
+                    let ours = SlicedArcArray::<$primitive>::DTYPE;
+                    let dtype = match promotion_operand(other.to_owned(), vm) {
+                        Ok(Operand::Array(dtype)) => ours.promote(dtype),
+                        Ok(Operand::Scalar(dtype)) => ours.promote_scalar(dtype),
+                        Err(_) => return Ok(Either::B(PyComparisonValue::NotImplemented)),
+                    };
+                    if dtype != ours {
+                        let zelf = AnyArray::from(zelf.arr.clone()).into_dtype(dtype);
+                        let zelf = with_array!(zelf, zelf => zelf.cast().to_pyobject(vm));
+                        return Ok(Either::A(zelf.rich_compare(other.to_owned(), op, vm)?));
+                    }
+
+                    let other = match AnyArray::try_from_object(vm, other.to_owned()) {
+                        Ok(other) => SlicedArcArray::<$primitive>::from_any(other.into_dtype(ours))
+                            .expect("Converted to our dtype"),
+                        Err(_) => array_or_scalar::<$primitive>(other.to_owned(), vm)?,
+                    };
+                    let mask = broadcast_zip(&zelf.arr, &other, vm, |x, y| {
+                        x.partial_cmp(&y)
+                            .map_or(op == PyComparisonOp::Ne, |ord| op.eval_ord(ord))
+                    })?;
+                    Ok(Either::A(mask_to_pyobject(mask, vm)))
+                }
+
                 #[pymethod(magic)]
                 fn len(&self, _vm: &VirtualMachine) -> PyResult<PyInt> {
                     let len = self.arr.read(|sliced| sliced.len());
                     Ok(len.into())
                 }
 
+                // Scalar conversions, only for arrays of one element
+                #[pymethod(magic)]
+                fn bool(&self, vm: &VirtualMachine) -> PyResult<bool> {
+                    let x = self
+                        .arr
+                        .only_element()
+                        .ok_or_else(|| ambiguous_truth(&self.arr, vm))?;
+                    Ok(x.is_nonzero())
+                }
+
+                #[pymethod(magic)]
+                fn float(&self, vm: &VirtualMachine) -> PyResult<f64> {
+                    Ok(only_scalar(&self.arr, vm)?.as_())
+                }
+
+                #[pymethod(magic)]
+                fn int(&self, vm: &VirtualMachine) -> PyResult<PyIntRef> {
+                    only_scalar(&self.arr, vm)?.to_pyobject(vm).try_int(vm)
+                }
+
                 // Stringy methods
                 #[pymethod(magic)]
                 fn str(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyStrRef> {
@@ -115,6 +376,567 @@ pub mod pyndarray {
                     .to_pyobject(vm))
                 }
 
+                #[pymethod(magic)]
+                fn deepcopy(
+                    zelf: PyRef<Self>,
+                    memo: OptionalArg<PyObjectRef>,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    deep_copy(zelf.as_object(), memo, vm, || {
+                        Self { arr: zelf.arr.sliced_copy() }.to_pyobject(vm)
+                    })
+                }
+
+                #[pymethod(name = "copy")]
+                fn copy_with_order(&self, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+                    let order = pop_order_kwarg(&mut kw, Order::C, vm)?;
+                    let owned = self.arr.read(|sliced| to_owned_with_order(sliced, order));
+                    Ok(SlicedArcArray::from_array(owned).cast().to_pyobject(vm))
+                }
+
+                /// A new array object viewing the same data, so that writes through either show
+                /// in the other. Its `base` is the data it views, as for any other view. Given a
+                /// `dtype` of the same itemsize, the bits of each element are read as that dtype.
+                #[pymethod]
+                fn view(&self, dtype: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult {
+                    let ours = SlicedArcArray::<$primitive>::DTYPE;
+                    let dtype = match dtype.into_option() {
+                        Some(dtype) => parse_dtype(dtype, vm)?,
+                        None => ours,
+                    };
+
+                    if dtype == ours {
+                        let arr = self.arr.append_slice(empty_slice_like(&self.arr), vm)?;
+                        return Ok(Self { arr }.to_pyobject(vm));
+                    }
+
+                    // Bool is one byte, so it never gets past this to `with_dtype!`
+                    if dtype.itemsize() != ours.itemsize() {
+                        return Err(vm.new_value_error(format!(
+                            "Cannot view {} as {}, as the item sizes differ",
+                            ours.stringy_key(),
+                            dtype.stringy_key(),
+                        )));
+                    }
+
+                    Ok(with_dtype!(dtype, U => self.arr.view_as::<U>().cast().to_pyobject(vm)))
+                }
+
+                /// The elements as raw little-endian bytes, in row-major order
+                #[pymethod]
+                fn tobytes(&self, vm: &VirtualMachine) -> PyRef<PyBytes> {
+                    vm.ctx.new_bytes(self.arr.read(npy::to_le_bytes))
+                }
+
+                /// Pickles as the dtype, shape and raw elements, which `_reconstruct` rebuilds
+                #[pymethod(magic)]
+                fn reduce(&self, vm: &VirtualMachine) -> PyResult<PyTupleRef> {
+                    reduce_array(&self.arr, Self::class(&vm.ctx), vm)
+                }
+
+                /// A copy converted to `dtype`. With `copy=False`, this is the array itself if it
+                /// already has the dtype and memory order asked for.
+                #[pymethod]
+                #[allow(clippy::unnecessary_cast)]
+                fn astype(
+                    zelf: PyRef<Self>,
+                    dtype: PyObjectRef,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let dtype = parse_dtype(dtype, vm)?;
+                    let order = pop_order_kwarg(&mut kw, Order::Keep, vm)?;
+                    let copy = pop_bool_kwarg(&mut kw, "copy", true, vm)?;
+
+                    if !copy
+                        && dtype == SlicedArcArray::<$primitive>::DTYPE
+                        && zelf.arr.read(|sliced| is_in_order(&sliced, order))
+                    {
+                        return Ok(zelf.into());
+                    }
+
+                    let owned = zelf.arr.read(|sliced| to_owned_with_order(sliced, order));
+
+                    if dtype == DataType::Bool {
+                        let mask = owned.mapv(Element::is_nonzero);
+                        return Ok(SlicedArcArray::from_array(mask).cast().to_pyobject(vm));
+                    }
+
+                    // mapv keeps the memory order of contiguous arrays, so the layout survives
+                    with_dtype!(dtype, T => {
+                        Ok(SlicedArcArray::from_array(owned.mapv(|x| x as T))
+                            .cast()
+                            .to_pyobject(vm))
+                    })
+                }
+
+                #[pygetset]
+                fn shape(&self, vm: &VirtualMachine) -> PyTupleRef {
+                    let shape: Vec<PyObjectRef> = self
+                        .arr
+                        .shape()
+                        .into_iter()
+                        .map(|len| vm.ctx.new_int(len).into())
+                        .collect();
+                    vm.ctx.new_tuple(shape)
+                }
+
+                #[pygetset]
+                fn dtype(&self, vm: &VirtualMachine) -> PyStrRef {
+                    vm.ctx
+                        .new_str(SlicedArcArray::<$primitive>::DTYPE.stringy_key())
+                }
+
+                /// The array this is a view into, or None if it owns its data. This is a new
+                /// object over the same data, rather than the very object it was sliced from.
+                #[pygetset]
+                fn base(&self) -> Option<Self> {
+                    self.arr.base().map(|arr| Self { arr })
+                }
+
+                /// Byte strides of the (sliced) array, as in numpy
+                #[pygetset]
+                fn strides(&self, vm: &VirtualMachine) -> PyTupleRef {
+                    let itemsize = SlicedArcArray::<$primitive>::DTYPE.itemsize() as isize;
+                    let strides: Vec<PyObjectRef> = self.arr.read(|sliced| {
+                        sliced
+                            .strides()
+                            .iter()
+                            .map(|stride| vm.ctx.new_int(stride * itemsize).into())
+                            .collect()
+                    });
+                    vm.ctx.new_tuple(strides)
+                }
+
+                #[pymethod]
+                fn argmax(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| arr.argmax(axis, vm))
+                }
+
+                #[pymethod]
+                fn argmin(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| arr.argmin(axis, vm))
+                }
+
+                #[pymethod]
+                fn sum(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = pop_accumulator_dtype(AnyArray::from(self.arr.clone()), &mut kw, vm)?;
+                    let mask = pop_where_kwarg(&mut kw, &self.arr.shape(), vm)?;
+                    with_array!(arr, arr => {
+                        // Masked out elements add nothing
+                        let arr = match mask {
+                            Some(mask) => arr.read(|arr| {
+                                let kept = ndarray::Zip::from(arr)
+                                    .and(&mask)
+                                    .map_collect(|&x, &keep| if keep { x } else { Zero::zero() });
+                                SlicedArcArray::from_array(kept)
+                            }),
+                            None => arr,
+                        };
+                        reduce_keepdims(&arr, axis, &mut kw, vm, |arr, axis| {
+                            arr.fold(axis, Zero::zero(), vm, Element::py_add)
+                        })
+                    })
+                }
+
+                /// The mean of every element, or along `axis`. Integers give float64. With a
+                /// `where=` mask, only the elements where it holds count, and a lane with none of
+                /// them gives NaN.
+                #[pymethod]
+                fn mean(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let mask = pop_where_kwarg(&mut kw, &self.arr.shape(), vm)?;
+                    with_float_array!(AnyArray::from(self.arr.clone()), arr => {
+                        let pairs = arr.read(|arr| match &mask {
+                            Some(mask) => ndarray::Zip::from(arr)
+                                .and(mask)
+                                .map_collect(|&x, &keep| (x, keep)),
+                            None => arr.mapv(|x| (x, true)),
+                        });
+                        let pairs = SlicedArcArray::from_array(pairs);
+                        reduce_keepdims(&pairs, axis, &mut kw, vm, |pairs, axis| {
+                            generic_pyndarray::masked_mean(pairs, axis, vm)
+                        })
+                    })
+                }
+
+                /// The largest element, or the largest along `axis`. Any NaN gives NaN; see
+                /// `nd.nanmax` to ignore them.
+                #[pymethod]
+                fn max(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| arr.max(axis, vm))
+                }
+
+                /// The smallest element, or the smallest along `axis`. Any NaN gives NaN; see
+                /// `nd.nanmin` to ignore them.
+                #[pymethod]
+                fn min(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| arr.min(axis, vm))
+                }
+
+                #[pymethod]
+                fn prod(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = pop_accumulator_dtype(AnyArray::from(self.arr.clone()), &mut kw, vm)?;
+                    with_array!(arr, arr => {
+                        reduce_keepdims(&arr, axis, &mut kw, vm, |arr, axis| {
+                            arr.fold(axis, One::one(), vm, Element::py_mul)
+                        })
+                    })
+                }
+
+                /// Copy of the diagonal `offset` above the main one (below, if negative)
+                #[pymethod]
+                fn diagonal(
+                    &self,
+                    offset: OptionalArg<isize>,
+                    vm: &VirtualMachine,
+                ) -> PyResult<Self> {
+                    let diagonal = self.arr.diagonal(offset.unwrap_or(0), vm)?;
+                    Ok(Self {
+                        arr: SlicedArcArray::from_array(diagonal),
+                    })
+                }
+
+                /// Sum along the diagonal `offset` above the main one
+                #[pymethod]
+                fn trace(
+                    &self,
+                    offset: OptionalArg<isize>,
+                    vm: &VirtualMachine,
+                ) -> PyResult<$primitive> {
+                    let diagonal = self.arr.diagonal(offset.unwrap_or(0), vm)?;
+                    Ok(diagonal.iter().fold(Zero::zero(), |acc, &x| acc.py_add(x)))
+                }
+
+                /// A copy with the new `shape`, in which one length may be -1
+                #[pymethod]
+                fn reshape(&self, shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                    let shape = py_new_shape_to_rust(shape, vm)?;
+                    let arr = self.arr.reshape(&shape, vm)?;
+                    Ok(Self { arr })
+                }
+
+                /// A 1-D copy, reading the elements in row-major order, or column-major with
+                /// `order='F'`
+                #[pymethod]
+                fn flatten(&self, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<Self> {
+                    let order = pop_order_kwarg(&mut kw, Order::C, vm)?;
+                    let flat = self.arr.read(|sliced| flattened_in_order(sliced, order));
+                    Ok(Self {
+                        arr: SlicedArcArray::from_array(flat),
+                    })
+                }
+
+                /// Copies of the elements at `indices` along `axis`, or through the flattened
+                /// array by default. Negative indices count back from the end.
+                #[pymethod]
+                fn take(
+                    &self,
+                    indices: ArgSequence<isize>,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult<Self> {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let taken = self.arr.take(&indices.into_vec(), axis, vm)?;
+                    Ok(Self {
+                        arr: SlicedArcArray::from_array(taken),
+                    })
+                }
+
+                /// Writes `values` into the flattened array at `indices`, in place. A sequence of
+                /// values repeats if it's shorter than `indices`.
+                #[pymethod]
+                fn put(
+                    &self,
+                    indices: ArgSequence<isize>,
+                    values: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    let values: Vec<$primitive> =
+                        match ArgSequence::<$primitive>::try_from_object(vm, values.clone()) {
+                            Ok(values) => values.into_vec(),
+                            Err(_) => vec![TryFromObject::try_from_object(vm, values)?],
+                        };
+                    self.arr.put(&indices.into_vec(), &values, vm)
+                }
+
+                /// Iterates over every element in row-major order, whatever the dimensions
+                #[pygetset]
+                fn flat(&self, vm: &VirtualMachine) -> PyResult {
+                    let elements: Vec<PyObjectRef> = self
+                        .arr
+                        .read(|sliced| sliced.iter().map(|x| x.to_pyobject(vm)).collect());
+                    let elements = vm.ctx.new_list(elements);
+                    vm.call_special_method(elements.as_object(), identifier!(vm, __iter__), ())
+                }
+
+                /// The transpose, as a view sharing our data
+                #[pygetset(name = "T")]
+                fn t(&self, vm: &VirtualMachine) -> PyResult<Self> {
+                    let arr = self.arr.transpose(None, vm)?;
+                    Ok(Self { arr })
+                }
+
+                /// A view with the axes in the order given, as separate arguments or a tuple, or
+                /// reversed when there are none
+                #[pymethod]
+                fn transpose(&self, axes: PosArgs, vm: &VirtualMachine) -> PyResult<Self> {
+                    let axes = transpose_axes(axes.into_vec(), vm)?;
+                    let arr = self.arr.transpose(axes.as_deref(), vm)?;
+                    Ok(Self { arr })
+                }
+
+                /// A view reversed along `axis`, or along every axis by default
+                #[pymethod]
+                fn flip(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult<Self> {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = self.arr.flip(axis, vm)?;
+                    Ok(Self { arr })
+                }
+
+                /// A view with a new length-1 axis inserted at `axis`
+                #[pymethod]
+                fn expand_dims(&self, axis: isize, vm: &VirtualMachine) -> PyResult<Self> {
+                    let arr = self.arr.expand_dims(axis, vm)?;
+                    Ok(Self { arr })
+                }
+
+                /// Drops length-1 axes, or just `axis`
+                #[pymethod]
+                fn squeeze(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult<Self> {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = self.arr.squeeze(axis, vm)?;
+                    Ok(Self { arr })
+                }
+
+                #[pymethod]
+                fn all(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| {
+                        arr.truth(axis, true, vm)
+                    })
+                }
+
+                #[pymethod]
+                fn any(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| {
+                        arr.truth(axis, false, vm)
+                    })
+                }
+
+                /// Indices of the nonzero elements, as an int64 array for each axis
+                #[pymethod]
+                fn nonzero(&self, vm: &VirtualMachine) -> PyTupleRef {
+                    let indices: Vec<PyObjectRef> = self
+                        .arr
+                        .nonzero()
+                        .into_iter()
+                        .map(|indices| {
+                            let indices = ndarray::Array1::from(indices).into_dyn();
+                            SlicedArcArray::from_array(indices).cast().to_pyobject(vm)
+                        })
+                        .collect();
+                    vm.ctx.new_tuple(indices)
+                }
+
+                /// Sorts in place along `axis`, the last by default
+                #[pymethod]
+                fn sort(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    match axis_arg_or(axis, &mut kw, -1, vm)? {
+                        Some(axis) => self.arr.sort_axis(axis, vm),
+                        None => Err(vm.new_value_error(
+                            "Sorting in place needs an axis; use nd.sort(a, axis=None) to flatten"
+                                .into(),
+                        )),
+                    }
+                }
+
+                /// Indices which would sort along `axis` (the last by default), or the flattened
+                /// array with `axis=None`
+                #[pymethod]
+                fn argsort(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg_or(axis, &mut kw, -1, vm)?;
+                    let indices = self.arr.argsort(axis, vm)?;
+                    let indices = indices.mapv(|index| index as i64);
+                    Ok(SlicedArcArray::from_array(indices).cast().to_pyobject(vm))
+                }
+
+                #[pymethod]
+                fn cumsum(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = pop_accumulator_dtype(AnyArray::from(self.arr.clone()), &mut kw, vm)?;
+                    with_array!(arr, arr => {
+                        let arr = arr.accumulate(axis, vm, Element::py_add)?;
+                        Ok(arr.cast().to_pyobject(vm))
+                    })
+                }
+
+                #[pymethod]
+                fn cumprod(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let arr = pop_accumulator_dtype(AnyArray::from(self.arr.clone()), &mut kw, vm)?;
+                    with_array!(arr, arr => {
+                        let arr = arr.accumulate(axis, vm, Element::py_mul)?;
+                        Ok(arr.cast().to_pyobject(vm))
+                    })
+                }
+
+                /// A copy limited to between `a_min` and `a_max`, as `nd.clip` does
+                #[pymethod]
+                fn clip(
+                    &self,
+                    a_min: PyObjectRef,
+                    a_max: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    clip_array(AnyArray::from(self.arr.clone()), a_min, a_max, vm)
+                }
+
+                #[pymethod]
+                fn var(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let ddof = pop_isize_kwarg(&mut kw, "ddof", 0, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| {
+                        with_float_array!(AnyArray::from(arr.clone()), arr => {
+                            arr.var(axis, ddof, vm)
+                        })
+                    })
+                }
+
+                #[pymethod]
+                fn std(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    let ddof = pop_isize_kwarg(&mut kw, "ddof", 0, vm)?;
+                    reduce_keepdims(&self.arr, axis, &mut kw, vm, |arr, axis| {
+                        with_float_array!(AnyArray::from(arr.clone()), arr => {
+                            arr.std(axis, ddof, vm)
+                        })
+                    })
+                }
+
+                /// The median of every element, or along `axis`, as `nd.median` gives it
+                #[pymethod]
+                fn median(
+                    &self,
+                    axis: OptionalArg<PyObjectRef>,
+                    mut kw: KwArgs,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    let axis = axis_arg(axis, &mut kw, vm)?;
+                    median_of(AnyArray::from(self.arr.clone()), axis, &mut kw, vm)
+                }
+
+                /// Number of elements
+                #[pygetset]
+                fn size(&self) -> usize {
+                    self.arr.read(|sliced| sliced.len())
+                }
+
+                /// Size of one element in bytes
+                #[pygetset]
+                fn itemsize(&self) -> usize {
+                    SlicedArcArray::<$primitive>::DTYPE.itemsize()
+                }
+
+                /// Total size of the elements in bytes
+                #[pygetset]
+                fn nbytes(&self) -> usize {
+                    self.size() * self.itemsize()
+                }
+
                 // AsNumber methods
                 #[pymethod(magic)]
                 fn iadd(
@@ -127,13 +949,23 @@ pub mod pyndarray {
                         empty_slice,
                         other,
                         vm,
-                        |mut dest, src, _vm| Ok(dest += &src),
-                        |mut dest, value, _vm| Ok(dest += value),
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_add(y));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| x.py_add(value));
+                            Ok(())
+                        },
                     )
                 }
 
                 #[pymethod(magic)]
                 fn add(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._add(&zelf, &other);
+                    }
+
                     let inst = $dtype {
                         arr: zelf.arr.sliced_copy(),
                     };
@@ -153,13 +985,23 @@ pub mod pyndarray {
                         empty_slice,
                         other,
                         vm,
-                        |mut dest, src, _vm| Ok(dest -= &src),
-                        |mut dest, value, _vm| Ok(dest -= value),
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_sub(y));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| x.py_sub(value));
+                            Ok(())
+                        },
                     )
                 }
 
                 #[pymethod(magic)]
                 fn sub(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._sub(&zelf, &other);
+                    }
+
                     let inst = $dtype {
                         arr: zelf.arr.sliced_copy(),
                     };
@@ -174,6 +1016,13 @@ pub mod pyndarray {
                     other: PyObjectRef,
                     vm: &VirtualMachine,
                 ) -> PyResult<()> {
+                    if !SlicedArcArray::<$primitive>::DTYPE.is_float() {
+                        return Err(vm.new_type_error(format!(
+                            "Cannot divide a {} array in place, as true division gives floats",
+                            SlicedArcArray::<$primitive>::DTYPE.stringy_key()
+                        )));
+                    }
+
                     let empty_slice = empty_slice_like(&zelf.arr);
                     zelf.assign_or_elem_fn(
                         empty_slice,
@@ -186,6 +1035,14 @@ pub mod pyndarray {
 
                 #[pymethod(magic)]
                 fn truediv(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if !SlicedArcArray::<$primitive>::DTYPE.is_float() {
+                        let zelf = float_operand(zelf.into(), vm);
+                        return vm._truediv(&zelf, &float_operand(other, vm));
+                    }
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._truediv(&zelf, &other);
+                    }
+
                     let inst = $dtype {
                         arr: zelf.arr.sliced_copy(),
                     };
@@ -205,13 +1062,23 @@ pub mod pyndarray {
                         empty_slice,
                         other,
                         vm,
-                        |mut dest, src, _vm| Ok(dest *= &src),
-                        |mut dest, value, _vm| Ok(dest *= value),
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_mul(y));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| x.py_mul(value));
+                            Ok(())
+                        },
                     )
                 }
 
                 #[pymethod(magic)]
                 fn mul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._mul(&zelf, &other);
+                    }
+
                     let inst = $dtype {
                         arr: zelf.arr.sliced_copy(),
                     };
@@ -221,15 +1088,408 @@ pub mod pyndarray {
                 }
 
                 #[pymethod(magic)]
-                fn neg(&self, vm: &VirtualMachine) -> PyResult {
-                    Ok(self.arr.write(|sliced| $dtype { arr: SlicedArcArray::from_array(sliced.to_owned()) }.to_pyobject(vm)))
-                }
-            }
-
-            impl $dtype {
-                pub fn assign_or_elem_fn<F, G, U>(
-                    &self,
-                    slice: DynamicSlice,
+                fn ifloordiv(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    let empty_slice = empty_slice_like(&zelf.arr);
+                    zelf.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_floordiv(y));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| x.py_floordiv(value));
+                            Ok(())
+                        },
+                    )
+                }
+
+                #[pymethod(magic)]
+                fn floordiv(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._floordiv(&zelf, &other);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    $dtype::ifloordiv(inst.clone(), other, vm)?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn imod(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    let empty_slice = empty_slice_like(&zelf.arr);
+                    zelf.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_mod(y));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| x.py_mod(value));
+                            Ok(())
+                        },
+                    )
+                }
+
+                #[pymethod(name = "__mod__")]
+                fn modulo(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._mod(&zelf, &other);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    $dtype::imod(inst.clone(), other, vm)?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn divmod(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._divmod(&zelf, &other);
+                    }
+                    Self::divmod_with(zelf, other, vm, |x, y| x.py_divmod(y))
+                }
+
+                #[pymethod(magic)]
+                fn rdivmod(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._divmod(&other, &zelf);
+                    }
+                    Self::divmod_with(zelf, other, vm, |x, y| y.py_divmod(x))
+                }
+
+                #[pymethod(magic)]
+                fn ipow(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    let empty_slice = empty_slice_like(&zelf.arr);
+                    zelf.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, vm| {
+                            check_exponents(&src, vm)?;
+                            dest.zip_mut_with(&src, |x, &y| *x = x.py_pow(y));
+                            Ok(())
+                        },
+                        |mut dest, value, vm| {
+                            check_exponents([&value], vm)?;
+                            dest.mapv_inplace(|x| x.py_pow(value));
+                            Ok(())
+                        },
+                    )
+                }
+
+                #[pymethod(magic)]
+                fn pow(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._pow(&zelf, &other);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    $dtype::ipow(inst.clone(), other, vm)?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn radd(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    $dtype::add(zelf, other, vm)
+                }
+
+                #[pymethod(magic)]
+                fn rmul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    $dtype::mul(zelf, other, vm)
+                }
+
+                #[pymethod(magic)]
+                fn rsub(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._sub(&other, &zelf);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&inst.arr);
+                    inst.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = y.py_sub(*x));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| value.py_sub(x));
+                            Ok(())
+                        },
+                    )?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn rtruediv(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    if !SlicedArcArray::<$primitive>::DTYPE.is_float() {
+                        let zelf = float_operand(zelf.into(), vm);
+                        return vm._truediv(&float_operand(other, vm), &zelf);
+                    }
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._truediv(&other, &zelf);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&inst.arr);
+                    inst.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = y / *x);
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| value / x);
+                            Ok(())
+                        },
+                    )?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn rfloordiv(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._floordiv(&other, &zelf);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&inst.arr);
+                    inst.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = y.py_floordiv(*x));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| value.py_floordiv(x));
+                            Ok(())
+                        },
+                    )?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn rmod(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._mod(&other, &zelf);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&inst.arr);
+                    inst.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            dest.zip_mut_with(&src, |x, &y| *x = y.py_mod(*x));
+                            Ok(())
+                        },
+                        |mut dest, value, _vm| {
+                            dest.mapv_inplace(|x| value.py_mod(x));
+                            Ok(())
+                        },
+                    )?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn rpow(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    if let Some(zelf) = Self::promoted(&zelf, &other, vm) {
+                        return vm._pow(&other, &zelf);
+                    }
+
+                    let inst = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let inst = inst.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&inst.arr);
+                    inst.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, vm| {
+                            check_exponents(&dest, vm)?;
+                            dest.zip_mut_with(&src, |x, &y| *x = y.py_pow(*x));
+                            Ok(())
+                        },
+                        |mut dest, value, vm| {
+                            check_exponents(&dest, vm)?;
+                            dest.mapv_inplace(|x| value.py_pow(x));
+                            Ok(())
+                        },
+                    )?;
+                    Ok(inst.into())
+                }
+
+                #[pymethod(magic)]
+                fn matmul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    let other = array_or_scalar::<$primitive>(other, vm)?;
+                    let product = generic_pyndarray::matmul(&zelf.arr, &other, vm)?;
+                    Ok(scalar_or_array(product, vm))
+                }
+
+                /// In place where the product keeps our shape, or else replacing our data, which
+                /// only an array without views can do
+                #[pymethod(magic)]
+                fn imatmul(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                ) -> PyResult<()> {
+                    let other = array_or_scalar::<$primitive>(other, vm)?;
+                    let product = generic_pyndarray::matmul(&zelf.arr, &other, vm)?;
+                    // `a @= a` shares our data, which mustn't count as another view
+                    drop(other);
+                    zelf.arr.store(product, vm)
+                }
+
+                #[pymethod(magic)]
+                fn rmatmul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                    let other = array_or_scalar::<$primitive>(other, vm)?;
+                    let product = generic_pyndarray::matmul(&other, &zelf.arr, vm)?;
+                    Ok(scalar_or_array(product, vm))
+                }
+
+                #[pymethod(magic)]
+                fn neg(&self, vm: &VirtualMachine) -> PyResult {
+                    Ok(self.arr.write(|sliced| $dtype { arr: SlicedArcArray::from_array(sliced.to_owned()) }.to_pyobject(vm)))
+                }
+            }
+
+            impl $dtype {
+                /// Calls `op` from a binary number slot, or `reflected` when we turn out to be the
+                /// right hand operand (e.g. `2.0 - a`)
+                fn binary_slot(
+                    a: &PyObject,
+                    b: &PyObject,
+                    vm: &VirtualMachine,
+                    op: fn(PyRef<Self>, PyObjectRef, &VirtualMachine) -> PyResult,
+                    reflected: fn(PyRef<Self>, PyObjectRef, &VirtualMachine) -> PyResult,
+                ) -> PyResult {
+                    if let Some(zelf) = a.downcast_ref::<Self>() {
+                        op(zelf.to_owned(), b.to_owned(), vm)
+                    } else if let Some(zelf) = b.downcast_ref::<Self>() {
+                        reflected(zelf.to_owned(), a.to_owned(), vm)
+                    } else {
+                        Ok(vm.ctx.not_implemented())
+                    }
+                }
+
+                /// Us converted to the dtype of arithmetic with `other`, if that's an array or
+                /// Python scalar which promotes ours to a wider dtype (such as float32 with a
+                /// float64 array, or int32 with a float)
+                fn promoted(
+                    zelf: &Py<Self>,
+                    other: &PyObject,
+                    vm: &VirtualMachine,
+                ) -> Option<PyObjectRef> {
+                    let ours = SlicedArcArray::<$primitive>::DTYPE;
+                    let dtype = match promotion_operand(other.to_owned(), vm).ok()? {
+                        Operand::Array(dtype) => ours.promote(dtype),
+                        Operand::Scalar(dtype) => ours.promote_scalar(dtype),
+                    };
+                    (dtype != ours).then(|| {
+                        with_array!(AnyArray::from(zelf.arr.clone()).into_dtype(dtype), arr => {
+                            arr.cast().to_pyobject(vm)
+                        })
+                    })
+                }
+
+                /// Computes the quotient and remainder of `op` together, in a single pass
+                fn divmod_with(
+                    zelf: PyRef<Self>,
+                    other: PyObjectRef,
+                    vm: &VirtualMachine,
+                    op: fn($primitive, $primitive) -> ($primitive, $primitive),
+                ) -> PyResult {
+                    let quotient = $dtype {
+                        arr: zelf.arr.sliced_copy(),
+                    };
+                    let quotient = quotient.into_ref(&vm.ctx);
+                    let empty_slice = empty_slice_like(&quotient.arr);
+                    let remainder = quotient.assign_or_elem_fn(
+                        empty_slice,
+                        other,
+                        vm,
+                        |mut dest, src, _vm| {
+                            let mut rem = dest.to_owned();
+                            ndarray::Zip::from(&mut dest)
+                                .and(&mut rem)
+                                .and(&src)
+                                .for_each(|q, r, &y| (*q, *r) = op(*q, y));
+                            Ok(rem)
+                        },
+                        |mut dest, value, _vm| {
+                            let mut rem = dest.to_owned();
+                            ndarray::Zip::from(&mut dest)
+                                .and(&mut rem)
+                                .for_each(|q, r| (*q, *r) = op(*q, value));
+                            Ok(rem)
+                        },
+                    )?;
+                    let remainder = $dtype {
+                        arr: SlicedArcArray::from_array(remainder),
+                    };
+                    Ok(vm
+                        .ctx
+                        .new_tuple(vec![quotient.into(), remainder.to_pyobject(vm)])
+                        .into())
+                }
+
+                pub fn assign_or_elem_fn<F, G, U>(
+                    &self,
+                    slice: DynamicSlice,
                     value: PyObjectRef,
                     vm: &VirtualMachine,
                     assign_fn: F,
@@ -250,12 +1510,27 @@ pub mod pyndarray {
                     if let Some(other_array) = value.downcast_ref::<$dtype>() {
                         self.arr
                             .assign_fn(slice, other_array.arr.clone(), vm, assign_fn)
+                    } else if let Ok(other) = AnyArray::try_from_object(vm, value.clone()) {
+                        // Arrays of other dtypes are converted to ours, narrowing if need be
+                        let other = SlicedArcArray::<$primitive>::from_any(
+                            other.into_dtype(SlicedArcArray::<$primitive>::DTYPE),
+                        )
+                        .expect("Converted to our dtype");
+                        self.arr.assign_fn(slice, other, vm, assign_fn)
+                    } else if value.downcast_ref::<PyList>().is_some()
+                        || value.downcast_ref::<PyTuple>().is_some()
+                    {
+                        // Nested sequences become an array of our dtype, as `array` makes them
+                        let other = py_nested_list_to_array::<$primitive>(value, vm)?;
+                        let other = SlicedArcArray::from_array(other);
+                        self.arr.assign_fn(slice, other, vm, assign_fn)
                     } else {
                         let value: $primitive = TryFromObject::try_from_object(vm, value)?;
                         self.arr.write(|mut sliced| {
+                            let slice = normalize_indices(&slice, sliced.shape(), vm)?;
                             if let Err(e) = sliced.bounds_check(&slice) {
                                 return Err(
-                                    vm.new_runtime_error(format!("Slice out of bounds; {e}"))
+                                    vm.new_index_error(format!("Slice out of bounds; {e}"))
                                 );
                             }
 
@@ -265,6 +1540,30 @@ pub mod pyndarray {
                 }
             }
 
+            impl AsBuffer for $dtype {
+                fn as_buffer(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyBuffer> {
+                    let (len, data) = zelf.arr.read(|sliced| {
+                        let data: Vec<u8> = sliced.iter().flat_map(|x| x.to_ne_bytes()).collect();
+                        (sliced.len(), data)
+                    });
+
+                    let itemsize = SlicedArcArray::<$primitive>::DTYPE.itemsize();
+                    let desc = BufferDescriptor {
+                        len: data.len(),
+                        readonly: true,
+                        itemsize,
+                        format: SlicedArcArray::<$primitive>::DTYPE.struct_format().into(),
+                        dim_desc: vec![(len, itemsize as isize, 0)],
+                    };
+
+                    Ok(PyBuffer::new(
+                        vm.ctx.new_bytes(data).into(),
+                        desc,
+                        &ARRAY_BUFFER_METHODS,
+                    ))
+                }
+            }
+
             impl AsMapping for $dtype {
                 fn as_mapping() -> &'static PyMappingMethods {
                     static AS_MAPPING: PyMappingMethods = PyMappingMethods {
@@ -304,11 +1603,7 @@ pub mod pyndarray {
                             Ok(a.to_owned())
                         }),
                         add: Some(|a, b, vm| {
-                            $dtype::add(
-                                $dtype::number_downcast_exact(a.to_number(), vm),
-                                b.to_owned(),
-                                vm,
-                            )
+                            $dtype::binary_slot(a, b, vm, $dtype::add, $dtype::radd)
                         }),
 
                         inplace_multiply: Some(|a, b, vm| {
@@ -320,11 +1615,7 @@ pub mod pyndarray {
                             Ok(a.to_owned())
                         }),
                         multiply: Some(|a, b, vm| {
-                            $dtype::mul(
-                                $dtype::number_downcast_exact(a.to_number(), vm),
-                                b.to_owned(),
-                                vm,
-                            )
+                            $dtype::binary_slot(a, b, vm, $dtype::mul, $dtype::rmul)
                         }),
 
                         inplace_true_divide: Some(|a, b, vm| {
@@ -336,11 +1627,7 @@ pub mod pyndarray {
                             Ok(a.to_owned())
                         }),
                         true_divide: Some(|a, b, vm| {
-                            $dtype::truediv(
-                                $dtype::number_downcast_exact(a.to_number(), vm),
-                                b.to_owned(),
-                                vm,
-                            )
+                            $dtype::binary_slot(a, b, vm, $dtype::truediv, $dtype::rtruediv)
                         }),
 
                         inplace_subtract: Some(|a, b, vm| {
@@ -352,12 +1639,76 @@ pub mod pyndarray {
                             Ok(a.to_owned())
                         }),
                         subtract: Some(|a, b, vm| {
-                            $dtype::sub(
+                            $dtype::binary_slot(a, b, vm, $dtype::sub, $dtype::rsub)
+                        }),
+
+                        inplace_floor_divide: Some(|a, b, vm| {
+                            $dtype::ifloordiv(
+                                $dtype::number_downcast_exact(a.to_number(), vm),
+                                b.to_owned(),
+                                vm,
+                            )?;
+                            Ok(a.to_owned())
+                        }),
+                        floor_divide: Some(|a, b, vm| {
+                            $dtype::binary_slot(a, b, vm, $dtype::floordiv, $dtype::rfloordiv)
+                        }),
+
+                        inplace_remainder: Some(|a, b, vm| {
+                            $dtype::imod(
+                                $dtype::number_downcast_exact(a.to_number(), vm),
+                                b.to_owned(),
+                                vm,
+                            )?;
+                            Ok(a.to_owned())
+                        }),
+                        remainder: Some(|a, b, vm| {
+                            $dtype::binary_slot(a, b, vm, $dtype::modulo, $dtype::rmod)
+                        }),
+
+                        inplace_matrix_multiply: Some(|a, b, vm| {
+                            $dtype::imatmul(
+                                $dtype::number_downcast_exact(a.to_number(), vm),
+                                b.to_owned(),
+                                vm,
+                            )?;
+                            Ok(a.to_owned())
+                        }),
+                        matrix_multiply: Some(|a, b, vm| {
+                            $dtype::binary_slot(a, b, vm, $dtype::matmul, $dtype::rmatmul)
+                        }),
+
+                        divmod: Some(|a, b, vm| {
+                            $dtype::binary_slot(a, b, vm, $dtype::divmod, $dtype::rdivmod)
+                        }),
+
+                        inplace_power: Some(|a, b, modulus, vm| {
+                            if !vm.is_none(modulus) {
+                                return Err(vm.new_type_error(
+                                    "pow() with a modulus is not supported for arrays".into(),
+                                ));
+                            }
+                            $dtype::ipow(
                                 $dtype::number_downcast_exact(a.to_number(), vm),
                                 b.to_owned(),
                                 vm,
-                            )
+                            )?;
+                            Ok(a.to_owned())
+                        }),
+                        power: Some(|a, b, modulus, vm| {
+                            if !vm.is_none(modulus) {
+                                return Err(vm.new_type_error(
+                                    "pow() with a modulus is not supported for arrays".into(),
+                                ));
+                            }
+                            $dtype::binary_slot(a, b, vm, $dtype::pow, $dtype::rpow)
+                        }),
+
+                        boolean: Some(|number, vm| $dtype::number_downcast(number).bool(vm)),
+                        float: Some(|number, vm| {
+                            Ok(vm.ctx.new_float($dtype::number_downcast(number).float(vm)?))
                         }),
+                        int: Some(|number, vm| $dtype::number_downcast(number).int(vm)),
 
                         ..PyNumberMethods::NOT_IMPLEMENTED
                     };
@@ -377,6 +1728,9 @@ pub mod pyndarray {
                             item: atomic_func!(|seq, i, vm| {
                                 $dtype::sequence_downcast(seq).getitem(i.to_pyobject(vm), vm)
                             }),
+                            contains: atomic_func!(|seq, needle, vm| {
+                                Ok($dtype::sequence_downcast(seq).contains(needle.to_owned(), vm))
+                            }),
                             ..PySequenceMethods::NOT_IMPLEMENTED
                         });
                     &AS_SEQUENCE
@@ -388,79 +1742,2156 @@ pub mod pyndarray {
                     Self { arr }
                 }
             }
+
+            impl From<SlicedArcArray<$primitive>> for AnyArray {
+                fn from(arr: SlicedArcArray<$primitive>) -> Self {
+                    AnyArray::$variant(arr)
+                }
+            }
         };
     }
 
-    build_pyarray!(f32, PyNdArrayFloat32, DataType::Float32);
-    build_pyarray!(f64, PyNdArrayFloat64, DataType::Float64);
+    /// Arrays are mutable, so they can't be hashed, just like lists
+    fn unhashable(zelf: &PyObject, vm: &VirtualMachine) -> PyResult<PyHash> {
+        Err(vm.new_type_error(format!("unhashable type: '{}'", zelf.class().name())))
+    }
 
-    #[pyfunction]
-    fn zeros(shape: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
-        let dtype = kw.pop_kwarg("dtype");
+    /// The error for the truth of an array with other than one element
+    fn ambiguous_truth<T>(arr: &SlicedArcArray<T>, vm: &VirtualMachine) -> PyBaseExceptionRef {
+        let len = arr.read(|sliced| sliced.len());
+        vm.new_value_error(format!(
+            "The truth value of an array with {len} elements is ambiguous. Use a.any() or a.all()"
+        ))
+    }
 
-        let shape = py_shape_to_rust(shape.into(), vm)?;
+    /// The element of a single element array, for `float()` and `int()`
+    fn only_scalar<T: Clone>(arr: &SlicedArcArray<T>, vm: &VirtualMachine) -> PyResult<T> {
+        let msg = "only arrays of one element can be converted to Python scalars";
+        arr.only_element()
+            .ok_or_else(|| vm.new_type_error(msg.into()))
+    }
+
+    /// `__deepcopy__` for arrays of any dtype. `copy` makes an array owning its own data, unless
+    /// Python's `memo` of what's been copied already has one for `zelf`, keeping the identity of
+    /// arrays which appear more than once.
+    fn deep_copy(
+        zelf: &PyObject,
+        memo: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+        copy: impl FnOnce() -> PyObjectRef,
+    ) -> PyResult {
+        let memo = memo.into_option().and_then(|memo| memo.downcast::<PyDict>().ok());
+        let Some(memo) = memo else {
+            return Ok(copy());
+        };
+        let key = vm.ctx.new_int(zelf.get_id());
+        if let Some(copied) = memo.get_item_opt(key.as_object(), vm)? {
+            return Ok(copied);
+        }
+
+        let copied = copy();
+        memo.set_item(key.as_object(), copied.clone(), vm)?;
+        Ok(copied)
+    }
+
+    /// `__reduce__` for arrays of any dtype: the module's `_reconstruct`, as kept on `class` by
+    /// `make_module`, and its arguments
+    fn reduce_array<T: npy::NpyElement>(
+        arr: &SlicedArcArray<T>,
+        class: &Py<PyType>,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyTupleRef>
+    where
+        SlicedArcArray<T>: GenericArray,
+    {
+        let reconstruct = class
+            .get_attr(vm.ctx.intern_str("_reconstruct"))
+            .ok_or_else(|| vm.new_type_error("Arrays can't be pickled before make_module".into()))?;
+        let dtype = vm.ctx.new_str(SlicedArcArray::<T>::DTYPE.stringy_key());
+        let shape: Vec<PyObjectRef> = arr
+            .shape()
+            .into_iter()
+            .map(|len| vm.ctx.new_int(len).into())
+            .collect();
+        let data = vm.ctx.new_bytes(arr.read(npy::to_le_bytes));
+        let args = vm.new_tuple((dtype, vm.ctx.new_tuple(shape), data));
+        Ok(vm.new_tuple((reconstruct, args)))
+    }
+
+    /// Buffers lent out by arrays. The buffer has to outlive any lock we could take on the array,
+    /// so it holds a contiguous copy of the data in a bytes object instead.
+    static ARRAY_BUFFER_METHODS: BufferMethods = BufferMethods {
+        obj_bytes: |buffer| buffer.obj_as::<PyBytes>().as_bytes().into(),
+        obj_bytes_mut: |_| unreachable!("Array buffers are read-only"),
+        release: |_| {},
+        retain: |_| {},
+    };
+
+    build_pyarray!(f32, PyNdArrayFloat32, Float32);
+    build_pyarray!(f64, PyNdArrayFloat64, Float64);
+    build_pyarray!(i32, PyNdArrayInt32, Int32);
+    build_pyarray!(i64, PyNdArrayInt64, Int64);
+
+    /// Arrays of bools, as masks. These have no arithmetic, only logic.
+    #[derive(PyPayload, Clone, Debug)]
+    #[pyclass(module = "ndarray", name)]
+    pub struct PyNdArrayBool {
+        pub arr: SlicedArcArray<bool>,
+    }
+
+    impl GenericArray for SlicedArcArray<bool> {
+        type PyArray = PyNdArrayBool;
+        const DTYPE: DataType = DataType::Bool;
+        fn cast(&self) -> Self::PyArray {
+            PyNdArrayBool { arr: self.clone() }
+        }
+        fn from_any(_any: AnyArray) -> Option<Self> {
+            // AnyArray only holds the numeric dtypes
+            None
+        }
+    }
+
+    #[pyclass(with(AsMapping, AsNumber, AsSequence))]
+    impl PyNdArrayBool {
+        #[pymethod(magic)]
+        fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            if let Some(mask) = needle.downcast_ref::<Self>() {
+                let picked = self.arr.masked(&mask.arr, vm)?;
+                return Ok(mask_to_pyobject(picked, vm));
+            }
+            self.arr.getitem(needle, vm)
+        }
+
+        #[pymethod(magic)]
+        fn len(&self) -> usize {
+            self.arr.length()
+        }
+
+        #[pyslot]
+        fn slot_hash(zelf: &PyObject, vm: &VirtualMachine) -> PyResult<PyHash> {
+            unhashable(zelf, vm)
+        }
+
+        #[pymethod]
+        fn tobytes(&self, vm: &VirtualMachine) -> PyRef<PyBytes> {
+            vm.ctx.new_bytes(self.arr.read(npy::to_le_bytes))
+        }
+
+        #[pymethod(magic)]
+        fn reduce(&self, vm: &VirtualMachine) -> PyResult<PyTupleRef> {
+            reduce_array(&self.arr, Self::class(&vm.ctx), vm)
+        }
+
+        #[pymethod(magic)]
+        fn deepcopy(
+            zelf: PyRef<Self>,
+            memo: OptionalArg<PyObjectRef>,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            deep_copy(zelf.as_object(), memo, vm, || {
+                Self { arr: zelf.arr.sliced_copy() }.to_pyobject(vm)
+            })
+        }
+
+        #[pymethod(magic)]
+        fn str(&self) -> String {
+            self.arr.to_string()
+        }
+
+        #[pymethod(magic)]
+        fn repr(&self) -> String {
+            self.arr.repr()
+        }
+
+        #[pygetset]
+        fn shape(&self, vm: &VirtualMachine) -> PyTupleRef {
+            let shape: Vec<PyObjectRef> = self
+                .arr
+                .shape()
+                .into_iter()
+                .map(|len| vm.ctx.new_int(len).into())
+                .collect();
+            vm.ctx.new_tuple(shape)
+        }
+
+        #[pygetset]
+        fn dtype(&self, vm: &VirtualMachine) -> PyStrRef {
+            vm.ctx.new_str(DataType::Bool.stringy_key())
+        }
+
+        #[pymethod(magic)]
+        fn and(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+            self.logic(other, vm, |x, y| x & y)
+        }
+
+        #[pymethod(magic)]
+        fn or(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+            self.logic(other, vm, |x, y| x | y)
+        }
+
+        #[pymethod(magic)]
+        fn invert(&self) -> Self {
+            Self {
+                arr: self.arr.map(|x| !x),
+            }
+        }
+
+        /// The one element of a 0-D or single element array. Anything bigger is ambiguous.
+        #[pymethod(magic)]
+        fn bool(&self, vm: &VirtualMachine) -> PyResult<bool> {
+            self.arr
+                .only_element()
+                .ok_or_else(|| ambiguous_truth(&self.arr, vm))
+        }
+    }
+
+    impl PyNdArrayBool {
+        /// Combines elementwise with a bool array or scalar by `op`, broadcasting
+        fn logic(
+            &self,
+            other: PyObjectRef,
+            vm: &VirtualMachine,
+            op: fn(bool, bool) -> bool,
+        ) -> PyResult<Self> {
+            let other = match other.downcast_ref::<Self>() {
+                Some(other) => other.arr.clone(),
+                None => {
+                    let value = bool::try_from_object(vm, other)?;
+                    let scalar = ndarray::ArrayD::from_elem(ndarray::IxDyn(&[]), value);
+                    SlicedArcArray::from_array(scalar)
+                }
+            };
+            let out = broadcast_zip(&self.arr, &other, vm, op)?;
+            Ok(Self {
+                arr: SlicedArcArray::from_array(out),
+            })
+        }
+
+        /// Calls `op` from a binary number slot, whichever side we're on, as the logical
+        /// operators are commutative
+        fn commutative_slot(
+            a: &PyObject,
+            b: &PyObject,
+            vm: &VirtualMachine,
+            op: fn(&Self, PyObjectRef, &VirtualMachine) -> PyResult<Self>,
+        ) -> PyResult {
+            if let Some(zelf) = a.downcast_ref::<Self>() {
+                Ok(op(zelf, b.to_owned(), vm)?.to_pyobject(vm))
+            } else if let Some(zelf) = b.downcast_ref::<Self>() {
+                Ok(op(zelf, a.to_owned(), vm)?.to_pyobject(vm))
+            } else {
+                Ok(vm.ctx.not_implemented())
+            }
+        }
+    }
+
+    impl AsMapping for PyNdArrayBool {
+        fn as_mapping() -> &'static PyMappingMethods {
+            static AS_MAPPING: PyMappingMethods = PyMappingMethods {
+                subscript: atomic_func!(|mapping, needle, vm| {
+                    PyNdArrayBool::mapping_downcast(mapping).getitem(needle.to_pyobject(vm), vm)
+                }),
+                length: atomic_func!(|mapping, _vm| {
+                    Ok(PyNdArrayBool::mapping_downcast(mapping).arr.length())
+                }),
+                ..PyMappingMethods::NOT_IMPLEMENTED
+            };
+            &AS_MAPPING
+        }
+    }
+
+    impl AsNumber for PyNdArrayBool {
+        fn as_number() -> &'static PyNumberMethods {
+            static AS_NUMBER: PyNumberMethods = PyNumberMethods {
+                and: Some(|a, b, vm| {
+                    PyNdArrayBool::commutative_slot(a, b, vm, PyNdArrayBool::and)
+                }),
+                or: Some(|a, b, vm| PyNdArrayBool::commutative_slot(a, b, vm, PyNdArrayBool::or)),
+                invert: Some(|number, vm| {
+                    Ok(PyNdArrayBool::number_downcast(number)
+                        .invert()
+                        .to_pyobject(vm))
+                }),
+                boolean: Some(|number, vm| PyNdArrayBool::number_downcast(number).bool(vm)),
+                ..PyNumberMethods::NOT_IMPLEMENTED
+            };
+            &AS_NUMBER
+        }
+    }
+
+    impl AsSequence for PyNdArrayBool {
+        fn as_sequence() -> &'static PySequenceMethods {
+            static AS_SEQUENCE: LazyLock<PySequenceMethods> = LazyLock::new(|| PySequenceMethods {
+                length: atomic_func!(|seq, _vm| {
+                    Ok(PyNdArrayBool::sequence_downcast(seq).arr.length())
+                }),
+                item: atomic_func!(|seq, i, vm| {
+                    PyNdArrayBool::sequence_downcast(seq).getitem(i.to_pyobject(vm), vm)
+                }),
+                ..PySequenceMethods::NOT_IMPLEMENTED
+            });
+            &AS_SEQUENCE
+        }
+    }
+
+    impl From<SlicedArcArray<bool>> for PyNdArrayBool {
+        fn from(arr: SlicedArcArray<bool>) -> Self {
+            Self { arr }
+        }
+    }
+
+    #[pyfunction]
+    fn zeros(shape: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let dtype = pop_creation_dtype(&mut kw, vm)?;
+        let shape = py_shape_to_rust(shape, vm)?;
+
+        with_dtype!(dtype, T => {
+            Ok(SlicedArcArray::from_array(ndarray::ArrayD::<T>::zeros(shape))
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// Arrays are always initialized, so this is the same as `zeros`
+    #[pyfunction]
+    fn empty(shape: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        zeros(shape, kw, vm)
+    }
+
+    #[pyfunction]
+    fn ones(shape: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let dtype = pop_creation_dtype(&mut kw, vm)?;
+        let shape = py_shape_to_rust(shape, vm)?;
+
+        with_dtype!(dtype, T => {
+            Ok(SlicedArcArray::from_array(ndarray::ArrayD::<T>::ones(shape))
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// Zeros in the shape and dtype of `a`, unless `dtype=` overrides it
+    #[pyfunction]
+    fn zeros_like(a: AnyArray, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let (shape, dtype) = shape_and_dtype_like(&a, &mut kw, vm)?;
+        with_dtype!(dtype, T => {
+            Ok(SlicedArcArray::from_array(ndarray::ArrayD::<T>::zeros(shape))
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// Arrays are always initialized, so this is the same as `zeros_like`
+    #[pyfunction]
+    fn empty_like(a: AnyArray, kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        zeros_like(a, kw, vm)
+    }
+
+    /// Ones in the shape and dtype of `a`, unless `dtype=` overrides it
+    #[pyfunction]
+    fn ones_like(a: AnyArray, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let (shape, dtype) = shape_and_dtype_like(&a, &mut kw, vm)?;
+        with_dtype!(dtype, T => {
+            Ok(SlicedArcArray::from_array(ndarray::ArrayD::<T>::ones(shape))
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// The shape of `a`, and the dtype for a new array like it: its own, or from `dtype=`
+    fn shape_and_dtype_like(
+        a: &AnyArray,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<(Vec<usize>, DataType)> {
+        let shape = with_array!(a, a => a.shape());
+        let dtype = pop_dtype_kwarg(kw, vm)?.unwrap_or(a.dtype());
+        Ok((shape, numeric_dtype(dtype, vm)?))
+    }
+
+    /// An `n` by `m` (default `n`) array of zeros, with ones on the diagonal `k` above the main
+    /// one
+    #[pyfunction]
+    fn eye(
+        n: usize,
+        m: OptionalArg<usize>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let dtype = pop_creation_dtype(&mut kw, vm)?;
+        let k = pop_isize_kwarg(&mut kw, "k", 0, vm)?;
+        let m = m.unwrap_or(n);
+
+        with_dtype!(dtype, T => {
+            let eye = ndarray::ArrayD::<T>::from_shape_fn(vec![n, m], |i| {
+                if i[1] as isize - i[0] as isize == k {
+                    T::one()
+                } else {
+                    T::zero()
+                }
+            });
+            Ok(SlicedArcArray::from_array(eye).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn full(
+        shape: PyObjectRef,
+        fill_value: PyObjectRef,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let dtype = pop_creation_dtype(&mut kw, vm)?;
+        let shape = py_shape_to_rust(shape, vm)?;
+
+        with_dtype!(dtype, T => {
+            let fill_value: T = fill_element(fill_value, dtype, vm)?;
+            Ok(SlicedArcArray::from_array(ndarray::ArrayD::from_elem(shape, fill_value))
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// `value` as an element of a `dtype` array to fill. As in numpy, integer dtypes truncate
+    /// floats toward zero, but raise OverflowError for anything they can't hold, rather than
+    /// wrapping it, and ValueError for NaN.
+    fn fill_element<T: TryFromObject + NumCast>(
+        value: PyObjectRef,
+        dtype: DataType,
+        vm: &VirtualMachine,
+    ) -> PyResult<T> {
+        if dtype.is_float() {
+            return T::try_from_object(vm, value);
+        }
+        let out_of_bounds = |value: &dyn std::fmt::Display| {
+            vm.new_overflow_error(format!(
+                "Python value {value} out of bounds for {}",
+                dtype.stringy_key()
+            ))
+        };
+
+        if value.fast_isinstance(vm.ctx.types.float_type) {
+            let value = f64::try_from_object(vm, value)?;
+            if value.is_nan() {
+                return Err(vm.new_value_error("cannot convert float NaN to integer".to_owned()));
+            }
+            return num_traits::cast(value.trunc()).ok_or_else(|| out_of_bounds(&value));
+        }
+        let int = value.try_index(vm)?;
+        T::try_from_object(vm, int.clone().into()).map_err(|_| out_of_bounds(int.as_bigint()))
+    }
+
+    #[pyfunction]
+    fn array(data: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let dtype = pop_dtype_kwarg(&mut kw, vm)?.unwrap_or(DataType::Float32);
+
+        if dtype == DataType::Bool {
+            return Ok(SlicedArcArray::from_array(py_nested_list_to_array::<bool>(data, vm)?)
+                .cast()
+                .to_pyobject(vm));
+        }
+
+        // Elements convert as they would for `full`, so that integer dtypes truncate floats
+        let elements = py_nested_list_to_array::<PyObjectRef>(data, vm)?;
+        with_dtype!(dtype, T => {
+            let flat = elements
+                .iter()
+                .map(|element| fill_element::<T>(element.clone(), dtype, vm))
+                .collect::<PyResult<Vec<_>>>()?;
+            let arr = ndarray::ArrayD::from_shape_vec(elements.raw_dim(), flat)
+                .map_err(|e| vm.new_value_error(format!("{e}")))?;
+            Ok(SlicedArcArray::from_array(arr).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn arange(
+        start_or_stop_a: ArgIntoFloat,
+        stop: OptionalArg<ArgIntoFloat>,
+        step: OptionalArg<ArgIntoFloat>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let dtype = pop_dtype_kwarg(&mut kw, vm)?.unwrap_or(DataType::Float32);
+        let dtype = numeric_dtype(dtype, vm)?;
+
+        let start_or_stop_a = *start_or_stop_a;
+        let stop = stop.into_option().map(|stop| *stop);
+        let step = step.into_option().map(|step| *step);
+
+        let (start, stop, step) = match (stop, step) {
+            (None, None) => (0.0, start_or_stop_a, 1.0),
+            (Some(stop), None) => (start_or_stop_a, stop, 1.0),
+            (Some(stop), Some(step)) => (start_or_stop_a, stop, step),
+            _ => unreachable!(),
+        };
+
+        if step == 0.0 {
+            return Err(vm.new_value_error("arange step must not be zero".into()));
+        }
+        let len = ((stop - start) / step).ceil().max(0.0) as usize;
+
+        with_dtype!(dtype, T => {
+            let range = ndarray::ArrayD::<T>::from_shape_fn(vec![len], |i| {
+                (start + i[0] as f64 * step).as_()
+            });
+            Ok(SlicedArcArray::from_array(range).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn concatenate(
+        arrays: ArgSequence<AnyArray>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = pop_isize_kwarg(&mut kw, "axis", 0, vm)?;
+
+        let arrays = arrays.into_vec();
+        let first = first_of(&arrays, vm)?;
+
+        with_array!(first, first => {
+            let arrays = same_dtype_as(&first, arrays, vm)?;
+            let joined = generic_pyndarray::concatenate(&arrays, axis, vm)?;
+            Ok(SlicedArcArray::from_array(joined).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn stack(arrays: ArgSequence<AnyArray>, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let axis = pop_isize_kwarg(&mut kw, "axis", 0, vm)?;
+
+        let arrays = arrays.into_vec();
+        let first = first_of(&arrays, vm)?;
+
+        with_array!(first, first => {
+            let arrays = same_dtype_as(&first, arrays, vm)?;
+            let stacked = generic_pyndarray::stack(&arrays, axis, vm)?;
+            Ok(SlicedArcArray::from_array(stacked).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn vstack(arrays: ArgSequence<AnyArray>, vm: &VirtualMachine) -> PyResult {
+        let arrays = arrays.into_vec();
+        let first = first_of(&arrays, vm)?;
+
+        with_array!(first, first => {
+            let arrays = same_dtype_as(&first, arrays, vm)?;
+            let stacked = generic_pyndarray::vstack(&arrays, vm)?;
+            Ok(SlicedArcArray::from_array(stacked).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn hstack(arrays: ArgSequence<AnyArray>, vm: &VirtualMachine) -> PyResult {
+        let arrays = arrays.into_vec();
+        let first = first_of(&arrays, vm)?;
+
+        with_array!(first, first => {
+            let arrays = same_dtype_as(&first, arrays, vm)?;
+            let stacked = generic_pyndarray::hstack(&arrays, vm)?;
+            Ok(SlicedArcArray::from_array(stacked).cast().to_pyobject(vm))
+        })
+    }
+
+    /// Wraps up the result of an elementwise predicate
+    fn mask_to_pyobject(mask: ndarray::ArrayD<bool>, vm: &VirtualMachine) -> PyObjectRef {
+        SlicedArcArray::from_array(mask).cast().to_pyobject(vm)
+    }
+
+    /// Refuses negative exponents of an integer dtype, as numpy does, there being no integer
+    /// to give
+    fn check_exponents<'a, T>(
+        exps: impl IntoIterator<Item = &'a T>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()>
+    where
+        T: PartialOrd + Zero + 'a,
+        SlicedArcArray<T>: GenericArray,
+    {
+        if SlicedArcArray::<T>::DTYPE.is_float() || exps.into_iter().all(|exp| *exp >= T::zero()) {
+            Ok(())
+        } else {
+            Err(vm.new_value_error("Integers to negative integer powers are not allowed.".into()))
+        }
+    }
+
+    /// `obj` as a float64 array if it's an integer array, for true division. Anything else is
+    /// left alone.
+    fn float_operand(obj: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+        match AnyArray::try_from_object(vm, obj.clone()) {
+            Ok(arr) if !arr.dtype().is_float() => {
+                with_float_array!(arr, arr => arr.cast().to_pyobject(vm))
+            }
+            _ => obj,
+        }
+    }
+
+    /// The first of `arrays`, whose dtype the others must share
+    fn first_of(arrays: &[AnyArray], vm: &VirtualMachine) -> PyResult<AnyArray> {
+        arrays
+            .first()
+            .cloned()
+            .ok_or_else(|| vm.new_value_error("Need at least one array".into()))
+    }
+
+    /// Pops a `dtype=` kwarg, if one was given
+    fn pop_dtype_kwarg(kw: &mut KwArgs, vm: &VirtualMachine) -> PyResult<Option<DataType>> {
+        kw.pop_kwarg("dtype")
+            .map(|dtype| parse_dtype(dtype, vm))
+            .transpose()
+    }
+
+    /// The dtype of a new array, from either the `dtype=` kwarg or the dtype of a `like=` array.
+    /// An explicit dtype wins.
+    fn pop_creation_dtype(kw: &mut KwArgs, vm: &VirtualMachine) -> PyResult<DataType> {
+        let like = kw
+            .pop_kwarg("like")
+            .map(|like| AnyArray::try_from_object(vm, like))
+            .transpose()?;
+        let dtype = pop_dtype_kwarg(kw, vm)?;
+
+        numeric_dtype(
+            dtype
+                .or(like.map(|like| like.dtype()))
+                .unwrap_or(DataType::Float32),
+            vm,
+        )
+    }
+
+    /// `arr` converted to the dtype to accumulate in: that of a `dtype=` kwarg, if one was
+    /// given, or else int64 for int32 as in numpy, so that sums of many int32s don't overflow.
+    /// Summing float32 in float64 loses less precision.
+    fn pop_accumulator_dtype(
+        arr: AnyArray,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<AnyArray> {
+        let dtype = match pop_dtype_kwarg(kw, vm)? {
+            Some(dtype) => numeric_dtype(dtype, vm)?,
+            None if arr.dtype() == DataType::Int32 => DataType::Int64,
+            None => arr.dtype(),
+        };
+        Ok(arr.into_dtype(dtype))
+    }
+
+    /// Rejects the bool dtype, for functions that fill arrays with numbers
+    fn numeric_dtype(dtype: DataType, vm: &VirtualMachine) -> PyResult<DataType> {
+        match dtype {
+            DataType::Bool => Err(vm.new_type_error(
+                "dtype='bool' is only supported by array() and astype() so far".into(),
+            )),
+            dtype => Ok(dtype),
+        }
+    }
+
+    fn parse_dtype(dtype: PyObjectRef, vm: &VirtualMachine) -> PyResult<DataType> {
+        DataType::from_pyobject(&dtype, vm)
+            .ok_or_else(|| vm.new_runtime_error(format!("Unrecognized dtype {dtype:?}")))
+    }
+
+    /// Pops an `order=` kwarg, falling back to `default` when it's absent
+    fn pop_order_kwarg(kw: &mut KwArgs, default: Order, vm: &VirtualMachine) -> PyResult<Order> {
+        kw.pop_kwarg("order")
+            .map(|order| {
+                Order::from_pyobject(&order)
+                    .ok_or_else(|| vm.new_value_error(format!("Unrecognized order {order:?}")))
+            })
+            .transpose()
+            .map(|order| order.unwrap_or(default))
+    }
+
+    /// The `axis` argument of a reduction, given either positionally or as a kwarg. None, or no
+    /// axis at all, means the whole array.
+    pub(crate) fn axis_arg(
+        axis: OptionalArg<PyObjectRef>,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<isize>> {
+        axis.into_option()
+            .or_else(|| kw.pop_kwarg("axis"))
+            .filter(|axis| !vm.is_none(axis))
+            .map(|axis| isize::try_from_object(vm, axis))
+            .transpose()
+    }
+
+    /// Runs the reduction `reduce` of `arr` over `axis`. With `keepdims=True`, the reduced axis
+    /// (or every axis, if there's none) is kept with length 1, so that the result broadcasts
+    /// against `arr`.
+    fn reduce_keepdims<T: Clone>(
+        arr: &SlicedArcArray<T>,
+        axis: Option<isize>,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+        reduce: impl FnOnce(&SlicedArcArray<T>, Option<isize>) -> PyResult,
+    ) -> PyResult {
+        let keepdims = pop_bool_kwarg(kw, "keepdims", false, vm)?;
+        let ndim = arr.ndim();
+
+        match axis {
+            _ if !keepdims => reduce(arr, axis),
+            Some(axis) => {
+                let axis = generic_pyndarray::normalize_axis(axis, ndim, vm)?;
+                let reduced = reduce(arr, Some(axis as isize))?;
+                expand_reduced(reduced, axis, vm)
+            }
+            None if ndim == 0 => reduce(arr, None),
+            None => {
+                // Reduces all the elements at once as the last axis, all the others being length 1
+                let mut shape = vec![1; ndim];
+                shape[ndim - 1] = arr.read(|sliced| sliced.len());
+                let elems = arr.read(|sliced| sliced.iter().cloned().collect());
+                let flat = ndarray::ArrayD::from_shape_vec(shape, elems)
+                    .expect("Length matches the shape");
+                let reduced = reduce(&SlicedArcArray::from_array(flat), Some(ndim as isize - 1))?;
+                expand_reduced(reduced, ndim - 1, vm)
+            }
+        }
+    }
+
+    /// The `where=` mask of a reduction broadcast to `shape`, if one was given. Only the
+    /// elements where it holds are reduced.
+    fn pop_where_kwarg(
+        kw: &mut KwArgs,
+        shape: &[usize],
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<ndarray::ArrayD<bool>>> {
+        let Some(mask) = kw.pop_kwarg("where") else {
+            return Ok(None);
+        };
+        let mask = truth_mask(mask, vm)?.read(|mask| mask.to_owned());
+        let broadcast = mask.broadcast(shape).ok_or_else(|| {
+            vm.new_value_error(format!(
+                "where= mask of shape {:?} could not be broadcast to shape {shape:?}",
+                mask.shape()
+            ))
+        })?;
+        Ok(Some(broadcast.to_owned()))
+    }
+
+    /// Puts the axis a reduction took away back in, with length 1
+    fn expand_reduced(reduced: PyObjectRef, axis: usize, vm: &VirtualMachine) -> PyResult {
+        let axis = axis as isize;
+        if let Some(mask) = reduced.downcast_ref::<PyNdArrayBool>() {
+            return Ok(mask.arr.expand_dims(axis, vm)?.cast().to_pyobject(vm));
+        }
+        let reduced = AnyArray::try_from_object(vm, reduced)?;
+        with_array!(reduced, reduced => Ok(reduced.expand_dims(axis, vm)?.cast().to_pyobject(vm)))
+    }
+
+    /// As `axis_arg`, but giving `default` when no axis is passed at all. An explicit None is
+    /// still None.
+    fn axis_arg_or(
+        axis: OptionalArg<PyObjectRef>,
+        kw: &mut KwArgs,
+        default: isize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<isize>> {
+        match axis.into_option().or_else(|| kw.pop_kwarg("axis")) {
+            None => Ok(Some(default)),
+            Some(axis) if vm.is_none(&axis) => Ok(None),
+            Some(axis) => isize::try_from_object(vm, axis).map(Some),
+        }
+    }
+
+    /// Pops a float kwarg, falling back to `default` when it's absent
+    fn pop_f64_kwarg(
+        kw: &mut KwArgs,
+        name: &str,
+        default: f64,
+        vm: &VirtualMachine,
+    ) -> PyResult<f64> {
+        kw.pop_kwarg(name)
+            .map(|value| f64::try_from_object(vm, value))
+            .transpose()
+            .map(|value| value.unwrap_or(default))
+    }
+
+    /// Pops an integer kwarg, falling back to `default` when it's absent
+    fn pop_isize_kwarg(
+        kw: &mut KwArgs,
+        name: &str,
+        default: isize,
+        vm: &VirtualMachine,
+    ) -> PyResult<isize> {
+        kw.pop_kwarg(name)
+            .map(|value| isize::try_from_object(vm, value))
+            .transpose()
+            .map(|value| value.unwrap_or(default))
+    }
+
+    /// Pops a kwarg by its truthiness, falling back to `default` when it's absent
+    fn pop_bool_kwarg(
+        kw: &mut KwArgs,
+        name: &str,
+        default: bool,
+        vm: &VirtualMachine,
+    ) -> PyResult<bool> {
+        kw.pop_kwarg(name)
+            .map(|value| value.try_to_bool(vm))
+            .transpose()
+            .map(|value| value.unwrap_or(default))
+    }
+
+    /// Iterates over `(index, value)` pairs of `a`, in row-major order
+    #[pyfunction]
+    fn ndenumerate(a: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let pairs = match a.downcast_ref::<PyNdArrayBool>() {
+            Some(mask) => mask.arr.read(|sliced| indexed_pairs(sliced, vm)),
+            None => with_array!(AnyArray::try_from_object(vm, a)?, arr => {
+                arr.read(|sliced| indexed_pairs(sliced, vm))
+            }),
+        };
+        let pairs = vm.ctx.new_list(pairs);
+        vm.call_special_method(pairs.as_object(), identifier!(vm, __iter__), ())
+    }
+
+    fn indexed_pairs<T: Clone + ToPyObject>(
+        view: ArrayViewD<'_, T>,
+        vm: &VirtualMachine,
+    ) -> Vec<PyObjectRef> {
+        view.indexed_iter()
+            .map(|(index, x)| {
+                let index: Vec<PyObjectRef> =
+                    index.slice().iter().map(|&i| vm.ctx.new_int(i).into()).collect();
+                vm.new_tuple((vm.ctx.new_tuple(index), x.clone())).into()
+            })
+            .collect()
+    }
+
+    /// Sorted distinct values of `a`, flattened. With `return_index=True`, this also gives
+    /// where each first occurs in the flattened array; with `return_inverse=True`, the index of
+    /// the value at each element, which `values.take(inverse)` rebuilds it from; and with
+    /// `return_counts=True`, how many times each occurs. Extras follow the values in a tuple,
+    /// in that order.
+    #[pyfunction]
+    fn unique(a: AnyArray, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let return_index = pop_bool_kwarg(&mut kw, "return_index", false, vm)?;
+        let return_inverse = pop_bool_kwarg(&mut kw, "return_inverse", false, vm)?;
+        let return_counts = pop_bool_kwarg(&mut kw, "return_counts", false, vm)?;
+        let (values, indices, inverse, counts) = with_array!(a, a => {
+            let unique = a.unique();
+            let values = ndarray::Array1::from(unique.values).into_dyn();
+            let values = SlicedArcArray::from_array(values).cast().to_pyobject(vm);
+            (values, unique.indices, unique.inverse, unique.counts)
+        });
+
+        let index_array = |indices: Vec<i64>| {
+            let indices = ndarray::Array1::from(indices).into_dyn();
+            SlicedArcArray::from_array(indices).cast().to_pyobject(vm)
+        };
+        let mut out = vec![values];
+        if return_index {
+            out.push(index_array(indices));
+        }
+        if return_inverse {
+            out.push(index_array(inverse));
+        }
+        if return_counts {
+            out.push(index_array(counts));
+        }
+
+        if out.len() == 1 {
+            Ok(out.remove(0))
+        } else {
+            Ok(vm.ctx.new_tuple(out).into())
+        }
+    }
+
+    /// The dtype of arithmetic between `a` and `b`, which may be arrays, dtypes or Python scalars
+    #[pyfunction]
+    fn result_type(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyStrRef> {
+        Ok(vm.ctx.new_str(promoted_dtype(&a, &b, vm)?.stringy_key()))
+    }
+
+    /// What `result_type` gives, for the binary functions which take arrays or scalars on
+    /// either side
+    fn promoted_dtype(a: &PyObject, b: &PyObject, vm: &VirtualMachine) -> PyResult<DataType> {
+        let a = promotion_operand(a.to_owned(), vm)?;
+        let b = promotion_operand(b.to_owned(), vm)?;
+        Ok(match (a, b) {
+            (Operand::Array(a), Operand::Scalar(b)) => a.promote_scalar(b),
+            (Operand::Scalar(a), Operand::Array(b)) => b.promote_scalar(a),
+            (Operand::Array(a), Operand::Array(b)) | (Operand::Scalar(a), Operand::Scalar(b)) => {
+                a.promote(b)
+            }
+        })
+    }
+
+    /// What one side of an operation brings to dtype promotion
+    enum Operand {
+        /// An array, or a dtype itself
+        Array(DataType),
+        /// A Python scalar, as the widest dtype of its kind
+        Scalar(DataType),
+    }
+
+    fn promotion_operand(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<Operand> {
+        if obj.downcast_ref::<PyNdArrayBool>().is_some() {
+            return Ok(Operand::Array(DataType::Bool));
+        }
+        if let Ok(arr) = AnyArray::try_from_object(vm, obj.clone()) {
+            return Ok(Operand::Array(arr.dtype()));
+        }
+        if let Some(dtype) = DataType::from_pyobject(&obj, vm) {
+            return Ok(Operand::Array(dtype));
+        }
+
+        // bool comes first, being a subclass of int
+        let types = &vm.ctx.types;
+        if obj.fast_isinstance(types.bool_type) {
+            Ok(Operand::Scalar(DataType::Bool))
+        } else if obj.fast_isinstance(types.int_type) {
+            Ok(Operand::Scalar(DataType::Int64))
+        } else if obj.fast_isinstance(types.float_type) {
+            Ok(Operand::Scalar(DataType::Float64))
+        } else {
+            Err(vm.new_type_error(format!("Cannot find the dtype of a {}", obj.class().name())))
+        }
+    }
+
+    /// Whether `a` and `b` have the same shape and equal elements. Arrays of different dtypes
+    /// are compared by value.
+    #[pyfunction]
+    fn array_equal(a: AnyArray, b: AnyArray) -> bool {
+        if a.dtype() == b.dtype() {
+            with_array!(a, a => {
+                let b = SlicedArcArray::from_any(b).expect("Same dtype");
+                a.read(|a| b.read(|b| a == b))
+            })
+        } else {
+            let a = with_array!(a, a => a.read(|a| a.mapv(|x| -> f64 { x.as_() })));
+            let b = with_array!(b, b => b.read(|b| b.mapv(|x| -> f64 { x.as_() })));
+            a == b
+        }
+    }
+
+    /// Every product of an element of `a` with one of `b`, flattening both first. The dtype is
+    /// that of arithmetic between them.
+    #[pyfunction]
+    fn outer(a: AnyArray, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_array!(promoted_with(a, &b, vm)?, a => {
+            let b = converted_operand(b, vm)?;
+            let product = generic_pyndarray::outer(&a, &b);
+            Ok(SlicedArcArray::from_array(product).cast().to_pyobject(vm))
+        })
+    }
+
+    /// Sums of products over the last axes of `a` and `b`, which is a scalar for two vectors.
+    /// The dtype is that of arithmetic between them.
+    #[pyfunction]
+    fn inner(a: AnyArray, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_array!(promoted_with(a, &b, vm)?, a => {
+            let b = converted_operand(b, vm)?;
+            Ok(scalar_or_array(generic_pyndarray::inner(&a, &b, vm)?, vm))
+        })
+    }
+
+    /// Cross product of two 3-vectors, or of each pair along the last axes of `a` and `b` with
+    /// the leading axes broadcast. The dtype is that of arithmetic between them.
+    #[pyfunction]
+    fn cross(a: AnyArray, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_array!(promoted_with(a, &b, vm)?, a => {
+            let b = converted_operand(b, vm)?;
+            let product = generic_pyndarray::cross(&a, &b, vm)?;
+            Ok(SlicedArcArray::from_array(product).cast().to_pyobject(vm))
+        })
+    }
+
+    /// A view of `a` with its axes in the order `axes`, or reversed by default
+    #[pyfunction]
+    fn transpose(
+        a: AnyArray,
+        axes: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axes = axes.into_option().or_else(|| kw.pop_kwarg("axes"));
+        let axes = transpose_axes(axes.into_iter().collect(), vm)?;
+        with_array!(a, a => Ok(a.transpose(axes.as_deref(), vm)?.cast().to_pyobject(vm)))
+    }
+
+    /// The axes of a transpose, which may be separate ints or a single tuple. None, or none at
+    /// all, means reversing the axes.
+    fn transpose_axes(
+        mut axes: Vec<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<Vec<isize>>> {
+        match axes.len() {
+            0 => Ok(None),
+            1 => {
+                let axes = axes.pop().expect("One axis");
+                if vm.is_none(&axes) {
+                    Ok(None)
+                } else {
+                    py_new_shape_to_rust(axes, vm).map(Some)
+                }
+            }
+            _ => axes
+                .into_iter()
+                .map(|axis| isize::try_from_object(vm, axis))
+                .collect::<PyResult<_>>()
+                .map(Some),
+        }
+    }
+
+    /// Views of `a` split along `axis` (0 by default), into `indices_or_sections` pieces of equal
+    /// length or at each of a list of indices
+    #[pyfunction]
+    fn split(
+        a: AnyArray,
+        indices_or_sections: PyObjectRef,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?.unwrap_or(0);
+        split_into(a, indices_or_sections, axis, true, vm)
+    }
+
+    /// As `split`, but a number of sections needn't divide the axis. The first pieces are one
+    /// longer to make up the difference.
+    #[pyfunction]
+    fn array_split(
+        a: AnyArray,
+        indices_or_sections: PyObjectRef,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?.unwrap_or(0);
+        split_into(a, indices_or_sections, axis, false, vm)
+    }
+
+    fn split_into(
+        a: AnyArray,
+        indices_or_sections: PyObjectRef,
+        axis: isize,
+        equal: bool,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        with_array!(a, a => {
+            let axis = generic_pyndarray::normalize_axis(axis, a.ndim(), vm)?;
+            let len = a.shape()[axis];
+
+            let points = if indices_or_sections.downcast_ref::<PyInt>().is_some() {
+                let sections = usize::try_from_object(vm, indices_or_sections)?;
+                section_points(len, sections, equal, vm)?
+            } else {
+                ArgSequence::<isize>::try_from_object(vm, indices_or_sections)?.into_vec()
+            };
+
+            let pieces = a
+                .split_at(&points, axis, vm)?
+                .into_iter()
+                .map(|piece| piece.cast().to_pyobject(vm))
+                .collect();
+            Ok(vm.ctx.new_list(pieces).into())
+        })
+    }
+
+    /// Where to split an axis of length `len` into `sections` pieces. Unless they must be
+    /// `equal`, the first `len % sections` pieces are one longer.
+    fn section_points(
+        len: usize,
+        sections: usize,
+        equal: bool,
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<isize>> {
+        if sections == 0 {
+            return Err(vm.new_value_error("Number of sections must be more than 0".into()));
+        }
+        if equal && len % sections != 0 {
+            return Err(vm.new_value_error(format!(
+                "An axis of length {len} can't be split into {sections} equal sections"
+            )));
+        }
+
+        let (each, extra) = (len / sections, len % sections);
+        let mut point = 0;
+        Ok((0..sections - 1)
+            .map(|section| {
+                point += each + usize::from(section < extra);
+                point as isize
+            })
+            .collect())
+    }
+
+    /// How many elements of `a` are nonzero, in all or along `axis`
+    #[pyfunction]
+    fn count_nonzero(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.count_nonzero(axis, vm))
+        })
+    }
+
+    /// `a` with `constant_values` (0 by default) added around it, `pad_width` elements before
+    /// and after each axis
+    #[pyfunction]
+    fn pad(a: AnyArray, pad_width: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let constant_values = kw.pop_kwarg("constant_values");
+        with_array!(a, a => {
+            let widths = py_pad_width_to_rust(pad_width, a.ndim(), vm)?;
+            let value = match constant_values {
+                Some(value) => TryFromObject::try_from_object(vm, value)?,
+                None => Zero::zero(),
+            };
+            Ok(SlicedArcArray::from_array(a.pad(&widths, value)).cast().to_pyobject(vm))
+        })
+    }
+
+    /// `a` shifted cyclically by `shift` places along `axis`, or through the flattened array
+    #[pyfunction]
+    fn roll(
+        a: AnyArray,
+        shift: isize,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            let rolled = a.roll(shift, axis, vm)?;
+            Ok(SlicedArcArray::from_array(rolled).cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn expand_dims(a: AnyArray, axis: isize, vm: &VirtualMachine) -> PyResult {
+        with_array!(a, a => Ok(a.expand_dims(axis, vm)?.cast().to_pyobject(vm)))
+    }
+
+    /// A view with the columns (the second axis) reversed
+    #[pyfunction]
+    fn fliplr(m: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_array!(m, m => {
+            if m.ndim() < 2 {
+                return Err(vm.new_value_error("fliplr needs an array of at least 2-D".into()));
+            }
+            Ok(m.flip(Some(1), vm)?.cast().to_pyobject(vm))
+        })
+    }
+
+    /// A view with the rows (the first axis) reversed
+    #[pyfunction]
+    fn flipud(m: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_array!(m, m => {
+            if m.ndim() < 1 {
+                return Err(vm.new_value_error("flipud needs an array of at least 1-D".into()));
+            }
+            Ok(m.flip(Some(0), vm)?.cast().to_pyobject(vm))
+        })
+    }
+
+    #[pyfunction]
+    fn nanargmax(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nanargmax(axis, vm))
+        })
+    }
+
+    #[pyfunction]
+    fn nanargmin(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nanargmin(axis, vm))
+        })
+    }
+
+    /// The elements of `arr` at `indices` along `axis`, which is the last by default. This picks
+    /// out what `argsort`, or `argmax` with `keepdims=True`, found. An axis of None flattens
+    /// `arr` first.
+    #[pyfunction]
+    fn take_along_axis(
+        arr: AnyArray,
+        indices: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg_or(axis, &mut kw, -1, vm)?;
+        if indices.dtype().is_float() {
+            return Err(vm.new_index_error("Arrays used as indices must be of integer type".into()));
+        }
+        let indices = SlicedArcArray::<i64>::from_any(indices.into_dtype(DataType::Int64))
+            .expect("Converted to int64");
+        with_array!(arr, arr => {
+            let taken = arr.take_along_axis(&indices, axis, vm)?;
+            Ok(SlicedArcArray::from_array(taken).cast().to_pyobject(vm))
+        })
+    }
+
+    /// Elements of `x` where `condition` is true (or nonzero), and of `y` elsewhere. Scalars
+    /// broadcast, and the dtype is that of arithmetic between `x` and `y`.
+    #[pyfunction(name = "where")]
+    fn where_(
+        condition: PyObjectRef,
+        x: PyObjectRef,
+        y: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let condition = truth_mask(condition, vm)?.read(|mask| mask.to_owned());
+
+        with_dtype!(numeric_dtype(promoted_dtype(&x, &y, vm)?, vm)?, T => {
+            let x = converted_operand::<T>(x, vm)?;
+            let y = converted_operand::<T>(y, vm)?;
+            let picked = generic_pyndarray::where_(&condition, &x, &y, vm)?;
+            Ok(SlicedArcArray::from_array(picked).cast().to_pyobject(vm))
+        })
+    }
+
+    /// Whether `x1` and `x2` are both true (or nonzero) elementwise, broadcasting
+    #[pyfunction]
+    fn logical_and(x1: PyObjectRef, x2: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let (x1, x2) = (truth_mask(x1, vm)?, truth_mask(x2, vm)?);
+        Ok(mask_to_pyobject(broadcast_zip(&x1, &x2, vm, |x, y| x && y)?, vm))
+    }
+
+    /// Whether either of `x1` and `x2` is true (or nonzero) elementwise, broadcasting
+    #[pyfunction]
+    fn logical_or(x1: PyObjectRef, x2: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let (x1, x2) = (truth_mask(x1, vm)?, truth_mask(x2, vm)?);
+        Ok(mask_to_pyobject(broadcast_zip(&x1, &x2, vm, |x, y| x || y)?, vm))
+    }
+
+    /// The truth of each element of `obj`, which may be a bool array, a numeric array whose
+    /// nonzero elements are true, or a scalar as a 0-D mask
+    fn truth_mask(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<SlicedArcArray<bool>> {
+        if let Some(mask) = obj.downcast_ref::<PyNdArrayBool>() {
+            return Ok(mask.arr.clone());
+        }
+        let mask = match AnyArray::try_from_object(vm, obj.clone()) {
+            Ok(arr) => with_array!(arr, arr => arr.read(|arr| arr.mapv(Element::is_nonzero))),
+            Err(_) => ndarray::ArrayD::from_elem(ndarray::IxDyn(&[]), obj.try_to_bool(vm)?),
+        };
+        Ok(SlicedArcArray::from_array(mask))
+    }
+
+    /// Elementwise maximum of two arrays, or an array and a scalar, in the dtype of arithmetic
+    /// between them. NaN wins, as in numpy.
+    #[pyfunction]
+    fn maximum(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_dtype!(numeric_dtype(promoted_dtype(&a, &b, vm)?, vm)?, T => {
+            let a = converted_operand::<T>(a, vm)?;
+            let b = converted_operand::<T>(b, vm)?;
+            let out = broadcast_zip(&a, &b, vm, |x, y| if x.is_nan() || x >= y { x } else { y })?;
+            Ok(SlicedArcArray::from_array(out).cast().to_pyobject(vm))
+        })
+    }
+
+    /// Elementwise minimum of two arrays, or an array and a scalar, in the dtype of arithmetic
+    /// between them. NaN wins, as in numpy.
+    #[pyfunction]
+    fn minimum(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_dtype!(numeric_dtype(promoted_dtype(&a, &b, vm)?, vm)?, T => {
+            let a = converted_operand::<T>(a, vm)?;
+            let b = converted_operand::<T>(b, vm)?;
+            let out = broadcast_zip(&a, &b, vm, |x, y| if x.is_nan() || x <= y { x } else { y })?;
+            Ok(SlicedArcArray::from_array(out).cast().to_pyobject(vm))
+        })
+    }
+
+    /// `base` raised to `exp` elementwise, as `**` does. Either may be a scalar, arrays
+    /// broadcast, and the dtype is that of arithmetic between them.
+    #[pyfunction]
+    fn power(base: PyObjectRef, exp: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_dtype!(numeric_dtype(promoted_dtype(&base, &exp, vm)?, vm)?, T => {
+            let base = converted_operand::<T>(base, vm)?;
+            let exp = converted_operand::<T>(exp, vm)?;
+            exp.read(|exp| check_exponents(&exp, vm))?;
+            let out = broadcast_zip(&base, &exp, vm, |x, y| x.py_pow(y))?;
+            Ok(SlicedArcArray::from_array(out).cast().to_pyobject(vm))
+        })
+    }
+
+    /// `a` with its elements limited to between `a_min` and `a_max`, either of which may be None
+    /// for no limit. The bounds may be arrays, which broadcast against `a`.
+    #[pyfunction]
+    fn clip(a: AnyArray, a_min: PyObjectRef, a_max: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        clip_array(a, a_min, a_max, vm)
+    }
+
+    fn clip_array(
+        a: AnyArray,
+        a_min: PyObjectRef,
+        a_max: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        with_array!(a, arr => {
+            // NaN stays NaN, and so does anything clipped by a NaN bound, as with maximum and
+            // minimum
+            let mut clipped = arr.sliced_copy();
+            if !vm.is_none(&a_min) {
+                let lower = converted_operand(a_min, vm)?;
+                let out = broadcast_zip(&clipped, &lower, vm, |x, lower| {
+                    if x.is_nan() || x >= lower { x } else { lower }
+                })?;
+                clipped = SlicedArcArray::from_array(out);
+            }
+            if !vm.is_none(&a_max) {
+                let upper = converted_operand(a_max, vm)?;
+                let out = broadcast_zip(&clipped, &upper, vm, |x, upper| {
+                    if x.is_nan() || x <= upper { x } else { upper }
+                })?;
+                clipped = SlicedArcArray::from_array(out);
+            }
+            Ok(clipped.cast().to_pyobject(vm))
+        })
+    }
+
+    /// `a` converted to the dtype of arithmetic with `b`, an array or Python scalar, which
+    /// `converted_operand` can then bring to the same dtype
+    fn promoted_with(a: AnyArray, b: &PyObject, vm: &VirtualMachine) -> PyResult<AnyArray> {
+        let dtype = match promotion_operand(b.to_owned(), vm)? {
+            Operand::Array(dtype) => a.dtype().promote(dtype),
+            Operand::Scalar(dtype) => a.dtype().promote_scalar(dtype),
+        };
+        Ok(a.into_dtype(dtype))
+    }
+
+    /// As `array_or_scalar`, but arrays of other dtypes are converted to `T`'s
+    fn converted_operand<T: TryFromObject>(
+        obj: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<SlicedArcArray<T>>
+    where
+        SlicedArcArray<T>: GenericArray,
+    {
+        match AnyArray::try_from_object(vm, obj.clone()) {
+            Ok(arr) => {
+                let arr = arr.into_dtype(SlicedArcArray::<T>::DTYPE);
+                Ok(SlicedArcArray::from_any(arr).expect("Converted to T's dtype"))
+            }
+            Err(_) => array_or_scalar(obj, vm),
+        }
+    }
+
+    /// A sorted copy of `a`, along `axis` (the last by default) or flattened with `axis=None`
+    #[pyfunction]
+    fn sort(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg_or(axis, &mut kw, -1, vm)?;
+        with_array!(a, a => {
+            let sorted = match axis {
+                Some(_) => a.sliced_copy(),
+                None => SlicedArcArray::from_array(a.read(|sliced| flattened(sliced))),
+            };
+            sorted.sort_axis(axis.unwrap_or(0), vm)?;
+            Ok(sorted.cast().to_pyobject(vm))
+        })
+    }
+
+    /// The median of every element of `a`, or along `axis`. An even count of elements gives
+    /// the mean of the middle two.
+    #[pyfunction]
+    fn median(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        median_of(a, axis, &mut kw, vm)
+    }
+
+    fn median_of(
+        a: AnyArray,
+        axis: Option<isize>,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        with_float_array!(a, a => {
+            reduce_keepdims(&a, axis, kw, vm, |a, axis| {
+                let medians = a.map_sorted_lanes(axis, vm, sorted_median)?;
+                Ok(scalar_or_array(medians, vm))
+            })
+        })
+    }
+
+    /// The `q`th percentiles of `a` (each from 0 to 100) over every element, or along `axis`,
+    /// interpolating linearly between the elements either side. A sequence of `q` gives a
+    /// leading axis with one result for each.
+    #[pyfunction]
+    fn percentile(
+        a: AnyArray,
+        q: PyObjectRef,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        quantiles_of(a, q, 100.0, axis, &mut kw, vm)
+    }
+
+    /// As `percentile`, but with each of `q` from 0 to 1
+    #[pyfunction]
+    fn quantile(
+        a: AnyArray,
+        q: PyObjectRef,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        quantiles_of(a, q, 1.0, axis, &mut kw, vm)
+    }
+
+    /// Quantiles of `a` for `q` given out of `scale`. With `keepdims=True`, the reduced axes
+    /// stay with length 1, after any leading axis for `q`.
+    fn quantiles_of(
+        a: AnyArray,
+        q: PyObjectRef,
+        scale: f64,
+        axis: Option<isize>,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let keepdims = pop_bool_kwarg(kw, "keepdims", false, vm)?;
+        let (qs, single) = match ArgSequence::<ArgIntoFloat>::try_from_object(vm, q.clone()) {
+            Ok(qs) => (qs.into_vec(), false),
+            Err(_) => (vec![ArgIntoFloat::try_from_object(vm, q)?], true),
+        };
+        let qs = qs
+            .into_iter()
+            .map(|q| {
+                let q = *q / scale;
+                if (0.0..=1.0).contains(&q) {
+                    Ok(q)
+                } else {
+                    Err(vm.new_value_error(format!("q must be between 0 and {scale}")))
+                }
+            })
+            .collect::<PyResult<Vec<f64>>>()?;
+
+        with_float_array!(a, a => {
+            let per_lane = a.map_sorted_lanes(axis, vm, |sorted| {
+                qs.iter().map(|&q| sorted_quantile(sorted, q)).collect::<Vec<_>>()
+            })?;
+
+            // One result for each of q along a new leading axis
+            let mut shape = vec![qs.len()];
+            shape.extend_from_slice(per_lane.shape());
+            let mut out = ndarray::ArrayD::from_shape_fn(shape, |idx| {
+                per_lane[&idx.slice()[1..]][idx[0]]
+            });
+
+            if keepdims {
+                let ndim = a.ndim();
+                match axis {
+                    Some(axis) => {
+                        let axis = generic_pyndarray::normalize_axis(axis, ndim, vm)?;
+                        out.insert_axis_inplace(ndarray::Axis(axis + 1));
+                    }
+                    None => {
+                        let mut shape = vec![1; ndim + 1];
+                        shape[0] = qs.len();
+                        out = out.into_shape_with_order(shape).expect("One result for each q");
+                    }
+                }
+            }
+
+            if single {
+                Ok(scalar_or_array(out.index_axis_move(ndarray::Axis(0), 0), vm))
+            } else {
+                Ok(SlicedArcArray::from_array(out).cast().to_pyobject(vm))
+            }
+        })
+    }
+
+    /// The largest element of `a`, or along `axis`, ignoring NaNs
+    #[pyfunction]
+    fn nanmax(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nanmax(axis, vm))
+        })
+    }
+
+    /// The smallest element of `a`, or along `axis`, ignoring NaNs
+    #[pyfunction]
+    fn nanmin(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nanmin(axis, vm))
+        })
+    }
+
+    #[pyfunction]
+    fn nansum(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_float_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nansum(axis, vm))
+        })
+    }
 
-        let dtype = dtype
-            .map(|dtype| {
-                DataType::from_pyobject(&dtype)
-                    .ok_or_else(|| vm.new_runtime_error(format!("Unrecognized dtype {dtype:?}")))
-            })
+    #[pyfunction]
+    fn nanmean(
+        a: AnyArray,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_float_array!(a, a => {
+            reduce_keepdims(&a, axis, &mut kw, vm, |a, axis| a.nanmean(axis, vm))
+        })
+    }
+
+    /// Integrates `y` along `axis` (the last by default) by the trapezoidal rule, with samples
+    /// at the positions `x` or spaced `dx` apart
+    #[pyfunction]
+    fn trapz(
+        y: AnyArray,
+        x: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let x = x
+            .into_option()
+            .or_else(|| kw.pop_kwarg("x"))
+            .filter(|x| !vm.is_none(x));
+        let dx = pop_f64_kwarg(&mut kw, "dx", 1.0, vm)?;
+        let axis = pop_isize_kwarg(&mut kw, "axis", -1, vm)?;
+
+        with_float_array!(y, y => {
+            let x = x.map(|x| array_or_scalar(x, vm)).transpose()?;
+            y.trapz(x.as_ref(), dx.as_(), axis, vm)
+        })
+    }
+
+    /// Running integral of `y` by the trapezoidal rule, as `trapz` takes it. The result is one
+    /// shorter than `y` along `axis`, unless an `initial` value is given to put first.
+    #[pyfunction]
+    fn cumulative_trapezoid(
+        y: AnyArray,
+        x: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let x = x
+            .into_option()
+            .or_else(|| kw.pop_kwarg("x"))
+            .filter(|x| !vm.is_none(x));
+        let dx = pop_f64_kwarg(&mut kw, "dx", 1.0, vm)?;
+        let axis = pop_isize_kwarg(&mut kw, "axis", -1, vm)?;
+        let initial = kw
+            .pop_kwarg("initial")
+            .filter(|initial| !vm.is_none(initial))
+            .map(|initial| f64::try_from_object(vm, initial))
             .transpose()?;
 
-        match dtype {
-            Some(DataType::Float64) => Ok(PyNdArrayFloat64::from(SlicedArcArray::from_array(
-                ndarray::ArrayD::zeros(shape),
-            ))
-            .to_pyobject(vm)),
-            None | Some(DataType::Float32) => Ok(PyNdArrayFloat32::from(
-                SlicedArcArray::from_array(ndarray::ArrayD::zeros(shape)),
-            )
-            .to_pyobject(vm)),
+        with_float_array!(y, y => {
+            let x = x.map(|x| array_or_scalar(x, vm)).transpose()?;
+            let initial = initial.map(|initial| initial.as_());
+            let integral = y.cumulative_trapezoid(x.as_ref(), dx.as_(), initial, axis, vm)?;
+            Ok(integral.cast().to_pyobject(vm))
+        })
+    }
+
+    /// The derivative of `f` along each axis, or along `axis` if given. The samples are a unit
+    /// apart, unless a spacing follows: a scalar distance or 1-D array of sample positions,
+    /// either one for every axis or one for each. Gives an array for each axis, in a tuple
+    /// unless there's only the one.
+    #[pyfunction]
+    fn gradient(
+        f: AnyArray,
+        spacing: PosArgs<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let edge_order = pop_isize_kwarg(&mut kw, "edge_order", 1, vm)?;
+        if edge_order != 1 {
+            return Err(vm.new_value_error("Only edge_order=1 is supported".into()));
+        }
+        let axes: Vec<isize> = match kw.pop_kwarg("axis").filter(|axis| !vm.is_none(axis)) {
+            None => (0..with_array!(&f, f => f.ndim()) as isize).collect(),
+            Some(axis) if axis.downcast_ref::<PyInt>().is_some() => {
+                vec![isize::try_from_object(vm, axis)?]
+            }
+            Some(axes) => ArgSequence::<isize>::try_from_object(vm, axes)?.into_vec(),
+        };
+
+        let mut spacing = spacing.into_vec();
+        let spacing = match spacing.len() {
+            0 => vec![vm.ctx.new_float(1.0).into(); axes.len()],
+            1 => vec![spacing.remove(0); axes.len()],
+            n if n == axes.len() => spacing,
+            n => {
+                return Err(vm.new_value_error(format!(
+                    "Expected a spacing for all {} axes, or one for every axis, got {n}",
+                    axes.len()
+                )))
+            }
+        };
+
+        let gradients = with_float_array!(f, f => {
+            axes.iter()
+                .zip(spacing)
+                .map(|(&axis, spacing)| {
+                    let gradient = match AnyArray::try_from_object(vm, spacing.clone()) {
+                        Ok(x) => {
+                            let x: Vec<_> = samples_1d(x, "x", vm)?
+                                .into_iter()
+                                .map(|x| x.as_())
+                                .collect();
+                            f.gradient(Some(x.as_slice()), One::one(), axis, vm)?
+                        }
+                        Err(_) => {
+                            let dx = f64::try_from_object(vm, spacing)?;
+                            f.gradient(None, dx.as_(), axis, vm)?
+                        }
+                    };
+                    Ok(SlicedArcArray::from_array(gradient).cast().to_pyobject(vm))
+                })
+                .collect::<PyResult<Vec<_>>>()?
+        });
+
+        match <[PyObjectRef; 1]>::try_from(gradients) {
+            Ok([gradient]) => Ok(gradient),
+            Err(gradients) => Ok(vm.ctx.new_tuple(gradients).into()),
         }
     }
 
+    /// Evaluates the polynomial with coefficients `p`, highest degree first, at each of `x`
     #[pyfunction]
-    fn arange(
-        start_or_stop_a: PyRef<PyFloat>,
-        stop: OptionalArg<PyRef<PyFloat>>,
-        step: OptionalArg<PyRef<PyFloat>>,
+    fn polyval(p: AnyArray, x: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(p, p => {
+            if p.ndim() != 1 {
+                return Err(vm.new_value_error("Coefficients must be 1-dimensional".into()));
+            }
+            let coeffs: Vec<_> = p.read(|p| p.iter().copied().collect());
+            let x = array_or_scalar(x, vm)?;
+            let values = x.read(|x| x.mapv(|x| polynomial::polyval(&coeffs, x)));
+            Ok(scalar_or_array(values, vm))
+        })
+    }
+
+    /// Least-squares polynomial fit of degree `deg` to the points `(x, y)`, giving coefficients
+    /// highest degree first
+    #[pyfunction]
+    fn polyfit(x: AnyArray, y: AnyArray, deg: usize, vm: &VirtualMachine) -> PyResult {
+        let dtype = x.dtype().to_float();
+        let x = samples_1d(x, "x", vm)?;
+        let y = samples_1d(y, "y", vm)?;
+        if x.len() != y.len() {
+            return Err(vm.new_value_error(format!(
+                "x and y must be the same length, got {} and {}",
+                x.len(),
+                y.len()
+            )));
+        }
+
+        let coeffs = polynomial::polyfit(&x, &y, deg)
+            .ok_or_else(|| vm.new_value_error("Singular matrix in polyfit".into()))?;
+
+        with_dtype!(dtype, T => {
+            let coeffs: Vec<T> = coeffs.iter().map(|&c| c.as_()).collect();
+            Ok(SlicedArcArray::from_array(ndarray::Array::from_vec(coeffs).into_dyn())
+                .cast()
+                .to_pyobject(vm))
+        })
+    }
+
+    /// Linear interpolation through the points `(xp, fp)` at each of `x`, as float64. `xp`
+    /// must be increasing, and beyond its ends gives `left` and `right`, which default to the
+    /// end values of `fp`. With a `period`, the points instead repeat every `period`, so `xp`
+    /// may be in any order and `x` wraps around.
+    #[pyfunction]
+    fn interp(
+        x: PyObjectRef,
+        xp: AnyArray,
+        fp: AnyArray,
         mut kw: KwArgs,
         vm: &VirtualMachine,
     ) -> PyResult {
-        let dtype = kw.pop_kwarg("dtype");
-        let dtype = dtype
-            .map(|dtype| {
-                DataType::from_pyobject(&dtype)
-                    .ok_or_else(|| vm.new_runtime_error(format!("Unrecognized dtype {dtype:?}")))
-            })
-            .transpose()?;
-        let dtype = dtype.unwrap_or(DataType::Float32);
+        let mut float_kwarg = |name: &str| {
+            kw.pop_kwarg(name)
+                .filter(|value| !vm.is_none(value))
+                .map(|value| f64::try_from_object(vm, value))
+                .transpose()
+        };
+        let left = float_kwarg("left")?;
+        let right = float_kwarg("right")?;
+        let period = float_kwarg("period")?;
 
-        let start_or_stop_a = start_or_stop_a.to_f64(); //pyint_to_isize(&start_or_stop_a, vm)?;
-        let stop = stop.as_option().map(|stop| stop.to_f64()); //pyint_to_isize(&stop, vm)).transpose()?;
-        let step = step.as_option().map(|step| step.to_f64()); //pyint_to_isize(&step, vm)).transpose()?;
+        let xp = samples_1d(xp, "xp", vm)?;
+        let fp = samples_1d(fp, "fp", vm)?;
+        if xp.len() != fp.len() {
+            return Err(vm.new_value_error(format!(
+                "xp and fp must be the same length, got {} and {}",
+                xp.len(),
+                fp.len()
+            )));
+        }
+        if xp.is_empty() {
+            return Err(vm.new_value_error("xp and fp must not be empty".into()));
+        }
 
-        let (start, stop, step) = match (stop, step) {
-            (None, None) => (0.0, start_or_stop_a, 1.0),
-            (Some(stop), None) => (start_or_stop_a, stop, 1.0),
-            (Some(stop), Some(step)) => (start_or_stop_a, stop, step),
-            _ => unreachable!(),
+        let x: ndarray::ArrayD<f64> = match AnyArray::try_from_object(vm, x.clone()) {
+            Ok(x) => with_array!(x, x => x.read(|x| x.mapv(|x| x.as_()))),
+            Err(_) => ndarray::arr0(f64::try_from_object(vm, x)?).into_dyn(),
         };
 
-        Ok(match dtype {
-            DataType::Float32 => SlicedArcArray::from_array(
-                ndarray::Array::range(start as f32, stop as f32, step as f32).into_dyn(),
-            )
-            .cast()
-            .to_pyobject(vm),
-            DataType::Float64 => {
-                SlicedArcArray::from_array(ndarray::Array::range(start, stop, step).into_dyn())
-                    .cast()
-                    .to_pyobject(vm)
+        let values = match period {
+            Some(period) => {
+                if period == 0.0 || period.is_nan() {
+                    return Err(vm.new_value_error("period must be a non-zero value".into()));
+                }
+                // Wrapping around, nothing is beyond the ends
+                let period = period.abs();
+                let (xp, fp) = interpolation::periodic_points(&xp, &fp, period);
+                let (first, last) = (fp[0], fp[fp.len() - 1]);
+                x.mapv(|x| interpolation::interp(x.rem_euclid(period), &xp, &fp, first, last))
+            }
+            None => {
+                let left = left.unwrap_or(fp[0]);
+                let right = right.unwrap_or(fp[fp.len() - 1]);
+                x.mapv(|x| interpolation::interp(x, &xp, &fp, left, right))
+            }
+        };
+        Ok(scalar_or_array(values, vm))
+    }
+
+    /// Copies out the elements of a 1-D array as f64s
+    fn samples_1d(arr: AnyArray, name: &str, vm: &VirtualMachine) -> PyResult<Vec<f64>> {
+        with_array!(arr, arr => {
+            if arr.ndim() != 1 {
+                return Err(vm.new_value_error(format!("{name} must be 1-dimensional")));
+            }
+            Ok(arr.read(|arr| arr.iter().map(|&x| x.as_()).collect()))
+        })
+    }
+
+    /// Counts of the flattened `a` in each of `bins` equal bins (10 by default) over `range`,
+    /// or over the samples if it isn't given. `bins` may instead be the bin edges themselves.
+    /// Gives the counts and the edges.
+    #[pyfunction]
+    fn histogram(
+        a: AnyArray,
+        bins: OptionalArg<PyObjectRef>,
+        range: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let bins = optional_arg(bins, "bins", &mut kw, vm);
+        let range = optional_arg(range, "range", &mut kw, vm);
+        let dtype = a.dtype().to_float();
+        let samples: Vec<f64> =
+            with_array!(a, a => a.read(|a| a.iter().map(|&x| x.as_()).collect()));
+
+        let edges = histogram_edges(bins, range, &samples, vm)?;
+        let counts = histogram::histogram(&samples, &edges);
+        let counts = SlicedArcArray::from_array(ndarray::Array1::from(counts).into_dyn());
+        let edges = float_vec_to_pyobject(&edges, dtype, vm);
+        Ok(vm.new_tuple((counts.cast().to_pyobject(vm), edges)).into())
+    }
+
+    /// Counts of the points `(x[i], y[i])` in a grid of bins, as a 2-D float64 array indexed by
+    /// the `x` bin then the `y` bin, with the edges along `x` and along `y`. `bins` is as for
+    /// `histogram`, or a pair of those to bin each differently, and `range` a pair of optional
+    /// `(min, max)` ranges.
+    #[pyfunction]
+    fn histogram2d(
+        x: AnyArray,
+        y: AnyArray,
+        bins: OptionalArg<PyObjectRef>,
+        range: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let bins = optional_arg(bins, "bins", &mut kw, vm);
+        let range = optional_arg(range, "range", &mut kw, vm);
+        let dtype = x.dtype().promote(y.dtype()).to_float();
+        let x = samples_1d(x, "x", vm)?;
+        let y = samples_1d(y, "y", vm)?;
+        if x.len() != y.len() {
+            return Err(vm.new_value_error(format!(
+                "x and y must be the same length, got {} and {}",
+                x.len(),
+                y.len()
+            )));
+        }
+
+        // As in numpy, a pair gives the bins for each axis, and anything else is for both
+        let (x_bins, y_bins) = match bins {
+            Some(bins) if bins.downcast_ref::<PyInt>().is_none() => {
+                let pair = ArgSequence::<PyObjectRef>::try_from_object(vm, bins.clone())?;
+                match <[PyObjectRef; 2]>::try_from(pair.into_vec()) {
+                    Ok([x_bins, y_bins]) => (Some(x_bins), Some(y_bins)),
+                    Err(_) => (Some(bins.clone()), Some(bins)),
+                }
+            }
+            bins => (bins.clone(), bins),
+        };
+        let (x_range, y_range) = match range {
+            Some(range) => {
+                let pair = ArgSequence::<PyObjectRef>::try_from_object(vm, range)?;
+                let [x_range, y_range] = <[PyObjectRef; 2]>::try_from(pair.into_vec())
+                    .map_err(|_| vm.new_value_error("range must be a pair of ranges".into()))?;
+                let given = |range: PyObjectRef| Some(range).filter(|range| !vm.is_none(range));
+                (given(x_range), given(y_range))
+            }
+            None => (None, None),
+        };
+        let x_edges = histogram_edges(x_bins, x_range, &x, vm)?;
+        let y_edges = histogram_edges(y_bins, y_range, &y, vm)?;
+
+        let counts = histogram::histogram2d(&x, &y, &x_edges, &y_edges);
+        let shape = (x_edges.len() - 1, y_edges.len() - 1);
+        let counts = ndarray::Array2::from_shape_vec(shape, counts.concat())
+            .expect("A count for each pair of bins")
+            .into_dyn();
+        Ok(vm
+            .new_tuple((
+                SlicedArcArray::from_array(counts).cast().to_pyobject(vm),
+                float_vec_to_pyobject(&x_edges, dtype, vm),
+                float_vec_to_pyobject(&y_edges, dtype, vm),
+            ))
+            .into())
+    }
+
+    /// An argument that may be given by position or keyword, where None is the same as absent
+    fn optional_arg(
+        arg: OptionalArg<PyObjectRef>,
+        name: &str,
+        kw: &mut KwArgs,
+        vm: &VirtualMachine,
+    ) -> Option<PyObjectRef> {
+        arg.into_option()
+            .or_else(|| kw.pop_kwarg(name))
+            .filter(|arg| !vm.is_none(arg))
+    }
+
+    /// The edges of a histogram's bins: `bins` of them (10 by default), evenly spaced over
+    /// `range`, or over the samples if it isn't given; or else the edges `bins` gives
+    fn histogram_edges(
+        bins: Option<PyObjectRef>,
+        range: Option<PyObjectRef>,
+        samples: &[f64],
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<f64>> {
+        let bins = match bins {
+            None => 10,
+            Some(bins) if bins.downcast_ref::<PyInt>().is_some() => {
+                usize::try_from_object(vm, bins)?
+            }
+            Some(edges) => {
+                let edges = ArgSequence::<f64>::try_from_object(vm, edges)?.into_vec();
+                if edges.is_empty()
+                    || edges.iter().any(|edge| edge.is_nan())
+                    || edges.windows(2).any(|pair| pair[0] > pair[1])
+                {
+                    return Err(vm.new_value_error("bins must increase monotonically".into()));
+                }
+                return Ok(edges);
+            }
+        };
+        if bins == 0 {
+            return Err(vm.new_value_error("bins must be positive".into()));
+        }
+
+        let (lo, hi) = match range {
+            Some(range) => {
+                let range = ArgSequence::<f64>::try_from_object(vm, range)?;
+                let [lo, hi] = <[f64; 2]>::try_from(range.into_vec())
+                    .map_err(|_| vm.new_value_error("range must be a pair (min, max)".into()))?;
+                if !lo.is_finite() || !hi.is_finite() {
+                    return Err(vm.new_value_error(format!("Range [{lo}, {hi}] is not finite")));
+                }
+                (lo, hi)
             }
+            None if samples.is_empty() => (0.0, 1.0),
+            None => {
+                // min and max would skip NaNs, which numpy refuses to find a range around
+                if samples.iter().any(|x| !x.is_finite()) {
+                    return Err(vm.new_value_error(
+                        "The range of the samples is not finite, so needs giving".into(),
+                    ));
+                }
+                samples.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| {
+                    (lo.min(x), hi.max(x))
+                })
+            }
+        };
+        if lo > hi {
+            return Err(vm.new_value_error(
+                "max must be larger than min in range parameter".into(),
+            ));
+        }
+        Ok(histogram::uniform_edges(lo, hi, bins))
+    }
+
+    /// A 1-D array of `values` as the float `dtype`
+    fn float_vec_to_pyobject(values: &[f64], dtype: DataType, vm: &VirtualMachine) -> PyObjectRef {
+        with_dtype!(dtype, T => {
+            let values: Vec<T> = values.iter().map(|&x| x.as_()).collect();
+            SlicedArcArray::from_array(ndarray::Array1::from(values).into_dyn())
+                .cast()
+                .to_pyobject(vm)
         })
     }
 
+    /// The covariance matrix of the variables in `m`, and in `y` if given. Each row is a
+    /// variable with an observation per column, or each column with `rowvar=False`. Divides by
+    /// `n - 1` for `n` observations, or by `n - ddof`, or by `n` with `bias=True`.
+    ///
+    /// By default a NaN spreads to every entry involving its variable, as in numpy;
+    /// `nan_policy="omit"` drops the observations with any NaN instead, and `"raise"` refuses
+    /// them.
+    #[pyfunction]
+    fn cov(
+        m: AnyArray,
+        y: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let bias = pop_bool_kwarg(&mut kw, "bias", false, vm)?;
+        let ddof = kw
+            .pop_kwarg("ddof")
+            .filter(|ddof| !vm.is_none(ddof))
+            .map(|ddof| isize::try_from_object(vm, ddof))
+            .transpose()?
+            .unwrap_or(if bias { 0 } else { 1 });
+        let cov = covariance_of(m, y, &mut kw, ddof as f64, vm)?;
+        Ok(square_to_pyobject(cov, vm))
+    }
+
+    /// Pearson correlation coefficients of the variables in `m`, and in `y` if given, laid out
+    /// as for `cov`, including its `nan_policy`
+    #[pyfunction]
+    fn corrcoef(
+        m: AnyArray,
+        y: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        // The divisor cancels out, so any will do
+        let cov = covariance_of(m, y, &mut kw, 1.0, vm)?;
+        Ok(square_to_pyobject(covariance::corrcoef(&cov), vm))
+    }
+
+    /// The covariance matrix for `cov` and `corrcoef`, after their shared arguments
+    fn covariance_of(
+        m: AnyArray,
+        y: OptionalArg<PyObjectRef>,
+        kw: &mut KwArgs,
+        ddof: f64,
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<Vec<f64>>> {
+        let y = optional_arg(y, "y", kw, vm)
+            .map(|y| AnyArray::try_from_object(vm, y))
+            .transpose()?;
+        let rowvar = pop_bool_kwarg(kw, "rowvar", true, vm)?;
+        let nan_policy = kw
+            .pop_kwarg("nan_policy")
+            .map(|policy| {
+                policy
+                    .downcast_ref::<PyStr>()
+                    .and_then(|policy| covariance::NanPolicy::from_name(policy.as_str()))
+                    .ok_or_else(|| {
+                        vm.new_value_error(format!("Unrecognized nan_policy {policy:?}"))
+                    })
+            })
+            .transpose()?
+            .unwrap_or(covariance::NanPolicy::Propagate);
+
+        let mut variables = variables_of(m, rowvar, "m", vm)?;
+        if let Some(y) = y {
+            let y = variables_of(y, rowvar, "y", vm)?;
+            let observations = |variables: &[Vec<f64>]| variables.first().map_or(0, Vec::len);
+            let (m_len, y_len) = (observations(&variables), observations(&y));
+            if m_len != y_len {
+                return Err(vm.new_value_error(format!(
+                    "m and y must have the same number of observations, got {m_len} and {y_len}"
+                )));
+            }
+            variables.extend(y);
+        }
+
+        match nan_policy {
+            covariance::NanPolicy::Propagate => {}
+            covariance::NanPolicy::Omit => {
+                variables = covariance::complete_observations(&variables);
+            }
+            covariance::NanPolicy::Raise => {
+                if variables.iter().flatten().any(|x| x.is_nan()) {
+                    return Err(vm.new_value_error("The observations contain NaN".into()));
+                }
+            }
+        }
+        Ok(covariance::cov(&variables, ddof))
+    }
+
+    /// The variables of a 1-D or 2-D array as rows of observations, with each row of a 2-D
+    /// array a variable if `rowvar`, or each column if not
+    fn variables_of(
+        arr: AnyArray,
+        rowvar: bool,
+        name: &str,
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<Vec<f64>>> {
+        with_array!(arr, arr => arr.read(|arr| {
+            let axis = match arr.ndim() {
+                1 => return Ok(vec![arr.iter().map(|&x| x.as_()).collect()]),
+                2 if rowvar => ndarray::Axis(0),
+                2 => ndarray::Axis(1),
+                ndim => {
+                    return Err(vm.new_value_error(format!(
+                        "{name} must be 1 or 2-dimensional, not {ndim}"
+                    )))
+                }
+            };
+            Ok(arr
+                .axis_iter(axis)
+                .map(|variable| variable.iter().map(|&x| x.as_()).collect())
+                .collect())
+        }))
+    }
+
+    /// A float64 matrix from its rows, or a plain float for a single variable, as numpy gives
+    fn square_to_pyobject(rows: Vec<Vec<f64>>, vm: &VirtualMachine) -> PyObjectRef {
+        if let [row] = &rows[..] {
+            if let [x] = row[..] {
+                return x.to_pyobject(vm);
+            }
+        }
+        let n = rows.len();
+        let matrix = ndarray::Array2::from_shape_vec((n, n), rows.concat())
+            .expect("A row of n for each of n variables");
+        SlicedArcArray::from_array(matrix.into_dyn()).cast().to_pyobject(vm)
+    }
+
+    #[pyfunction]
+    fn isnan(x: AnyArray, vm: &VirtualMachine) -> PyObjectRef {
+        let mask = with_array!(x, x => x.read(|sliced| sliced.mapv(|x| x.is_nan())));
+        mask_to_pyobject(mask, vm)
+    }
+
+    #[pyfunction]
+    fn isinf(x: AnyArray, vm: &VirtualMachine) -> PyObjectRef {
+        let mask = with_float_array!(x, x => x.read(|sliced| sliced.mapv(|x| x.is_infinite())));
+        mask_to_pyobject(mask, vm)
+    }
+
+    /// Whether every element of `a` is close to `b`, by `isclose`
+    #[pyfunction]
+    fn allclose(a: PyObjectRef, b: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<bool> {
+        Ok(close_mask(a, b, kw, vm)?.iter().all(|&close| close))
+    }
+
+    /// Elementwise `|a - b| <= atol + rtol * |b|`, with numpy's defaults of `rtol=1e-5` and
+    /// `atol=1e-8`. NaNs are only close with `equal_nan=True`.
+    #[pyfunction]
+    fn isclose(a: PyObjectRef, b: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        Ok(mask_to_pyobject(close_mask(a, b, kw, vm)?, vm))
+    }
+
+    fn close_mask(
+        a: PyObjectRef,
+        b: PyObjectRef,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<ndarray::ArrayD<bool>> {
+        let rtol = pop_f64_kwarg(&mut kw, "rtol", 1e-5, vm)?;
+        let atol = pop_f64_kwarg(&mut kw, "atol", 1e-8, vm)?;
+        let equal_nan = pop_bool_kwarg(&mut kw, "equal_nan", false, vm)?;
+
+        // Compared in float64 whatever the dtypes, so they needn't match
+        let a = converted_operand::<f64>(a, vm)?;
+        let b = converted_operand::<f64>(b, vm)?;
+        broadcast_zip(&a, &b, vm, |x, y| element::is_close(x, y, rtol, atol, equal_nan))
+    }
+
+    /// -1, 0 or 1 by the sign of each element
+    #[pyfunction]
+    fn sign(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_array!(x, x => Ok(x.map(|x| x.py_sign()).cast().to_pyobject(vm)))
+    }
+
+    /// 1/x for each element, so zeros become infinite. Integers give float64.
+    #[pyfunction]
+    fn reciprocal(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| 1.0 / x).cast().to_pyobject(vm)))
+    }
+
+    #[pyfunction]
+    fn floor(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.floor()).cast().to_pyobject(vm)))
+    }
+
+    #[pyfunction]
+    fn ceil(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.ceil()).cast().to_pyobject(vm)))
+    }
+
+    #[pyfunction]
+    fn log2(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.log2()).cast().to_pyobject(vm)))
+    }
+
+    #[pyfunction]
+    fn log10(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.log10()).cast().to_pyobject(vm)))
+    }
+
+    /// exp(x) - 1 for each element, accurate even where x is near zero
+    #[pyfunction]
+    fn expm1(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.exp_m1()).cast().to_pyobject(vm)))
+    }
+
+    /// log(1 + x) for each element, accurate even where x is near zero
+    #[pyfunction]
+    fn log1p(x: AnyArray, vm: &VirtualMachine) -> PyResult {
+        with_float_array!(x, x => Ok(x.map(|x| x.ln_1p()).cast().to_pyobject(vm)))
+    }
+
+    /// Rounds half to even, like numpy, at `decimals` places (default 0)
+    #[pyfunction]
+    fn round(x: AnyArray, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let decimals = pop_isize_kwarg(&mut kw, "decimals", 0, vm)?;
+        let decimals = i32::try_from(decimals)
+            .map_err(|_| vm.new_overflow_error(format!("decimals={decimals} is too large")))?;
+        with_array!(x, x => Ok(x.map(|x| x.py_round(decimals)).cast().to_pyobject(vm)))
+    }
+
     #[pyfunction]
     fn copy(
         obj: PyObjectRef,
@@ -468,22 +3899,218 @@ pub mod pyndarray {
     ) -> PyResult {
         vm.call_special_method(&obj, identifier!(vm, __copy__), ())
     }
+
+    /// Changes how every array is printed from now on: `precision=` places after the point for
+    /// floats, `threshold=` elements before an array is summarized, and `edgeitems=` elements
+    /// kept at each end of its axes when it is. Options left out, or None, stay as they were.
+    #[pyfunction]
+    fn set_printoptions(mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let precision = pop_usize_option(&mut kw, "precision", vm)?;
+        let threshold = pop_usize_option(&mut kw, "threshold", vm)?;
+        let edgeitems = pop_usize_option(&mut kw, "edgeitems", vm)?;
+
+        let mut options = PRINT_OPTIONS.lock().unwrap();
+        options.precision = precision.or(options.precision);
+        options.threshold = threshold.unwrap_or(options.threshold);
+        options.edgeitems = edgeitems.unwrap_or(options.edgeitems);
+        Ok(())
+    }
+
+    /// Pops a non-negative int kwarg, if one was given and isn't None
+    fn pop_usize_option(
+        kw: &mut KwArgs,
+        name: &str,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<usize>> {
+        kw.pop_kwarg(name)
+            .filter(|value| !vm.is_none(value))
+            .map(|value| usize::try_from_object(vm, value))
+            .transpose()
+    }
+
+    /// Writes `arr` to `path` in numpy's `.npy` format, adding the extension if it's missing
+    #[pyfunction]
+    fn save(path: PyStrRef, arr: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        let bytes = match arr.downcast_ref::<PyNdArrayBool>() {
+            Some(mask) => mask.arr.read(npy::to_npy),
+            None => {
+                let arr = AnyArray::try_from_object(vm, arr)?;
+                with_array!(arr, arr => arr.read(npy::to_npy))
+            }
+        };
+
+        let mut path = path.as_str().to_string();
+        if !path.ends_with(".npy") {
+            path.push_str(".npy");
+        }
+        std::fs::write(path, bytes).map_err(|e| e.into_pyexception(vm))
+    }
+
+    /// Reads an array from a `.npy` file, in the dtype it was saved with
+    #[pyfunction]
+    fn load(path: PyStrRef, vm: &VirtualMachine) -> PyResult {
+        let bytes = std::fs::read(path.as_str()).map_err(|e| e.into_pyexception(vm))?;
+        let (header, data) = npy::read_npy_header(&bytes, vm)?;
+        let dtype = npy::descr_to_dtype(&header.descr).ok_or_else(|| {
+            vm.new_type_error(format!("Unsupported .npy dtype {}", header.descr))
+        })?;
+
+        if dtype == DataType::Bool {
+            let mask = npy::read_npy_data::<bool>(&header, data, vm)?;
+            return Ok(mask_to_pyobject(mask, vm));
+        }
+
+        with_dtype!(dtype, T => {
+            let arr = npy::read_npy_data::<T>(&header, data, vm)?;
+            Ok(SlicedArcArray::from_array(arr).cast().to_pyobject(vm))
+        })
+    }
+
+    /// A 1-D array of the raw little-endian elements in `data`, which is anything bytes-like, of
+    /// the dtype given by `dtype=`
+    #[pyfunction]
+    fn frombuffer(data: ArgBytesLike, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult {
+        let dtype = pop_dtype_kwarg(&mut kw, vm)?.unwrap_or(DataType::Float32);
+        let itemsize = dtype.itemsize();
+
+        data.with_ref(|bytes| {
+            if bytes.len() % itemsize != 0 {
+                return Err(vm.new_value_error(format!(
+                    "A buffer of {} bytes isn't a whole number of {}-byte elements",
+                    bytes.len(),
+                    itemsize
+                )));
+            }
+            let shape = vec![bytes.len() / itemsize];
+
+            if dtype == DataType::Bool {
+                let mask = npy::from_le_bytes::<bool>(shape, bytes, vm)?;
+                return Ok(mask_to_pyobject(mask, vm));
+            }
+
+            with_dtype!(dtype, T => {
+                let arr = npy::from_le_bytes::<T>(shape, bytes, vm)?;
+                Ok(SlicedArcArray::from_array(arr).cast().to_pyobject(vm))
+            })
+        })
+    }
+
+    /// Rebuilds an array from the dtype, shape and raw elements given by its `__reduce__`
+    #[pyfunction]
+    fn _reconstruct(
+        dtype: PyObjectRef,
+        shape: PyObjectRef,
+        data: PyRef<PyBytes>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let dtype = parse_dtype(dtype, vm)?;
+        let shape = py_shape_to_rust(shape, vm)?;
+
+        if dtype == DataType::Bool {
+            let mask = npy::from_le_bytes::<bool>(shape, data.as_bytes(), vm)?;
+            return Ok(mask_to_pyobject(mask, vm));
+        }
+
+        with_dtype!(dtype, T => {
+            let arr = npy::from_le_bytes::<T>(shape, data.as_bytes(), vm)?;
+            Ok(SlicedArcArray::from_array(arr).cast().to_pyobject(vm))
+        })
+    }
 }
 
 impl DataType {
-    fn from_pyobject(obj: &PyObject) -> Option<Self> {
-        // TODO: Casts from float and integer primitives
+    /// A dtype from its name, or from the Python type of its scalars as numpy allows
+    fn from_pyobject(obj: &PyObject, vm: &VirtualMachine) -> Option<Self> {
+        let types = &vm.ctx.types;
+        if obj.is(types.float_type) {
+            return Some(Self::Float64);
+        }
+        if obj.is(types.int_type) {
+            return Some(Self::Int64);
+        }
+        if obj.is(types.bool_type) {
+            return Some(Self::Bool);
+        }
+
         match obj.downcast_ref::<PyStr>()?.as_str() {
             "float64" => Some(Self::Float64),
             "float32" => Some(Self::Float32),
+            "int64" => Some(Self::Int64),
+            "int32" => Some(Self::Int32),
+            "bool" => Some(Self::Bool),
             _ => None,
         }
     }
 
+    /// Size of one element in bytes
+    fn itemsize(&self) -> usize {
+        match self {
+            DataType::Float32 => std::mem::size_of::<f32>(),
+            DataType::Float64 => std::mem::size_of::<f64>(),
+            DataType::Int32 => std::mem::size_of::<i32>(),
+            DataType::Int64 => std::mem::size_of::<i64>(),
+            DataType::Bool => std::mem::size_of::<bool>(),
+        }
+    }
+
+    /// Format code of the element, in the notation of the `struct` module
+    fn struct_format(&self) -> &'static str {
+        match self {
+            DataType::Float32 => "f",
+            DataType::Float64 => "d",
+            DataType::Int32 => "i",
+            DataType::Int64 => "q",
+            DataType::Bool => "?",
+        }
+    }
+
     fn stringy_key(&self) -> &'static str {
         match self {
             DataType::Float32 => "float32",
             DataType::Float64 => "float64",
+            DataType::Int32 => "int32",
+            DataType::Int64 => "int64",
+            DataType::Bool => "bool",
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        matches!(self, DataType::Float32 | DataType::Float64)
+    }
+
+    /// The dtype which holds the values of both, as numpy promotes arrays
+    fn promote(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (DataType::Bool, other) | (other, DataType::Bool) => other,
+            (DataType::Int32, DataType::Int64) | (DataType::Int64, DataType::Int32) => {
+                DataType::Int64
+            }
+            // Every other mix has a float, and only float64 holds all of both
+            _ => DataType::Float64,
+        }
+    }
+
+    /// The dtype of arithmetic between an array of this dtype and a Python scalar, whose dtype
+    /// only says what kind of number it is. The array's dtype wins unless it can't hold that
+    /// kind of number at all.
+    fn promote_scalar(self, scalar: Self) -> Self {
+        match (self, scalar) {
+            (DataType::Bool, scalar) => scalar,
+            (DataType::Int32 | DataType::Int64, DataType::Float32 | DataType::Float64) => {
+                DataType::Float64
+            }
+            (array, _) => array,
+        }
+    }
+
+    /// The dtype of fractional results, such as a mean: floats keep their own, and integers
+    /// give float64
+    fn to_float(self) -> Self {
+        if self.is_float() {
+            self
+        } else {
+            DataType::Float64
         }
     }
 }
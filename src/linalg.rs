@@ -0,0 +1,126 @@
+//! `ndarray.linalg`, for the linear algebra beyond products, after `numpy.linalg`
+
+#[rustpython_vm::pymodule(name = "linalg")]
+pub mod pylinalg {
+    use crate::{
+        generic_pyndarray::{normalize_axis, scalar_or_array, SlicedArcArray},
+        pyndarray::axis_arg,
+        AnyArray, GenericArray,
+    };
+    use function::{ArgIntoFloat, KwArgs, OptionalArg};
+    use ndarray::{ArrayViewD, Axis};
+    use num_traits::Float;
+    use rustpython_vm::convert::ToPyObject;
+    use rustpython_vm::*;
+
+    /// numpy's `ord` for vector norms: the sum of magnitudes, the Euclidean length, or the
+    /// largest magnitude
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    enum NormOrder {
+        One,
+        Two,
+        Inf,
+    }
+
+    /// The norm of `a`, or of each lane along `axis`. By default that's the 2-norm, which for a
+    /// matrix is the Frobenius norm; `ord=1` and `ord=inf` are also supported, giving the
+    /// largest column and row sums of magnitudes for a matrix.
+    #[pyfunction]
+    fn norm(
+        a: AnyArray,
+        ord: OptionalArg<PyObjectRef>,
+        axis: OptionalArg<PyObjectRef>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let ord = ord
+            .into_option()
+            .or_else(|| kw.pop_kwarg("ord"))
+            .filter(|ord| !vm.is_none(ord))
+            .map(|ord| norm_order(ord, vm))
+            .transpose()?;
+        let axis = axis_arg(axis, &mut kw, vm)?;
+        with_float_array!(a, a => norm_of(&a, ord, axis, vm))
+    }
+
+    fn norm_order(ord: PyObjectRef, vm: &VirtualMachine) -> PyResult<NormOrder> {
+        let value = *ArgIntoFloat::try_from_object(vm, ord)?;
+        if value == 1.0 {
+            Ok(NormOrder::One)
+        } else if value == 2.0 {
+            Ok(NormOrder::Two)
+        } else if value == f64::INFINITY {
+            Ok(NormOrder::Inf)
+        } else {
+            Err(vm.new_value_error(format!("norm: Unsupported ord {value}")))
+        }
+    }
+
+    fn norm_of<T: Float + ToPyObject>(
+        a: &SlicedArcArray<T>,
+        ord: Option<NormOrder>,
+        axis: Option<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult
+    where
+        SlicedArcArray<T>: GenericArray,
+    {
+        let vector_ord = ord.unwrap_or(NormOrder::Two);
+
+        a.read(|a| match axis {
+            Some(axis) => {
+                let axis = Axis(normalize_axis(axis, a.ndim(), vm)?);
+                let norms = a.map_axis(axis, |lane| vector_norm(lane.iter().copied(), vector_ord));
+                Ok(scalar_or_array(norms, vm))
+            }
+            // Without an ord, any array is treated as one long vector
+            None if ord.is_none() || a.ndim() < 2 => {
+                Ok(vector_norm(a.iter().copied(), vector_ord).to_pyobject(vm))
+            }
+            None if a.ndim() == 2 => Ok(matrix_norm(a, vector_ord, vm)?.to_pyobject(vm)),
+            None => Err(vm.new_value_error(format!(
+                "norm: An ord needs a vector or matrix, or an axis, not {} dimensions",
+                a.ndim()
+            ))),
+        })
+    }
+
+    fn vector_norm<T: Float>(elems: impl Iterator<Item = T>, ord: NormOrder) -> T {
+        match ord {
+            NormOrder::One => elems.fold(T::zero(), |acc, x| acc + x.abs()),
+            NormOrder::Two => elems.fold(T::zero(), |acc, x| acc + x * x).sqrt(),
+            NormOrder::Inf => elems.fold(T::zero(), |acc, x| nan_max(acc, x.abs())),
+        }
+    }
+
+    /// The norm of the matrix `a` induced by the vector norm `ord`: the largest sum of
+    /// magnitudes down a column for `ord=1`, or along a row for `ord=inf`
+    fn matrix_norm<T: Float>(
+        a: &ArrayViewD<T>,
+        ord: NormOrder,
+        vm: &VirtualMachine,
+    ) -> PyResult<T> {
+        let axis = match ord {
+            NormOrder::One => Axis(0),
+            NormOrder::Inf => Axis(1),
+            NormOrder::Two => {
+                return Err(vm.new_value_error(
+                    "norm: ord=2 of a matrix (its largest singular value) isn't supported".into(),
+                ));
+            }
+        };
+        let sums = a.map_axis(axis, |lane| {
+            vector_norm(lane.iter().copied(), NormOrder::One)
+        });
+        Ok(sums.iter().fold(T::zero(), |acc, &x| nan_max(acc, x)))
+    }
+
+    /// The larger of `a` and `b`, or NaN if either is, unlike `Float::max` which drops NaN
+    fn nan_max<T: Float>(a: T, b: T) -> T {
+        if a.is_nan() || b.is_nan() {
+            T::nan()
+        } else {
+            a.max(b)
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! numpy's `.npy` file format: a magic string, then a header giving the dtype and shape as a
+//! Python dict literal, then the raw elements
+
+use ndarray::{ArrayD, ArrayViewD};
+use rustpython_vm::{PyResult, VirtualMachine};
+
+use crate::{generic_pyndarray::shape_repr, DataType};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Elements as they are stored in `.npy` files
+pub trait NpyElement: Sized {
+    /// numpy's descriptor of the dtype, with its byte order
+    const DESCR: &'static str;
+
+    fn write_le(&self, out: &mut Vec<u8>);
+
+    /// Reads an element from exactly `size_of::<Self>()` bytes
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_npy_element {
+    ($t:ty, $descr:literal) => {
+        impl NpyElement for $t {
+            const DESCR: &'static str = $descr;
+
+            fn write_le(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le(bytes: &[u8]) -> Self {
+                Self::from_le_bytes(bytes.try_into().expect("One element's worth of bytes"))
+            }
+        }
+    };
+}
+
+impl_npy_element!(f32, "<f4");
+impl_npy_element!(f64, "<f8");
+impl_npy_element!(i32, "<i4");
+impl_npy_element!(i64, "<i8");
+
+impl NpyElement for bool {
+    const DESCR: &'static str = "|b1";
+
+    fn write_le(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn read_le(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+}
+
+/// The dtype stored under `descr`, if it's one we have
+pub fn descr_to_dtype(descr: &str) -> Option<DataType> {
+    match descr {
+        "<f4" => Some(DataType::Float32),
+        "<f8" => Some(DataType::Float64),
+        "<i4" => Some(DataType::Int32),
+        "<i8" => Some(DataType::Int64),
+        "|b1" => Some(DataType::Bool),
+        _ => None,
+    }
+}
+
+/// The whole `.npy` file for `view`, in version 1.0 of the format
+pub fn to_npy<T: NpyElement>(view: ArrayViewD<'_, T>) -> Vec<u8> {
+    let shape = shape_repr(view.shape());
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {shape}, }}",
+        T::DESCR
+    );
+
+    // Padded with spaces and ended with a newline, so the data starts 64-byte aligned
+    let unpadded = MAGIC.len() + 4 + header.len() + 1;
+    header.extend(std::iter::repeat(' ').take((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let header_len = u16::try_from(header.len()).expect("Header fits in version 1.0");
+    let mut out = MAGIC.to_vec();
+    out.extend_from_slice(&[1, 0]);
+    out.extend_from_slice(&header_len.to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend(to_le_bytes(view));
+    out
+}
+
+/// The elements of `view` in row-major order, whatever the layout in memory
+pub fn to_le_bytes<T: NpyElement>(view: ArrayViewD<'_, T>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(view.len() * std::mem::size_of::<T>());
+    for x in view.iter() {
+        x.write_le(&mut out);
+    }
+    out
+}
+
+/// An array of `shape` from its elements in row-major order
+pub fn from_le_bytes<T: NpyElement>(
+    shape: Vec<usize>,
+    data: &[u8],
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let bytes = byte_count::<T>(&shape, vm)?;
+    if data.len() != bytes {
+        return Err(vm.new_value_error(format!(
+            "Expected {bytes} bytes for shape {shape:?}, got {}",
+            data.len()
+        )));
+    }
+
+    let elems: Vec<T> = data.chunks_exact(std::mem::size_of::<T>()).map(T::read_le).collect();
+    Ok(ArrayD::from_shape_vec(shape, elems).expect("Length matches the shape"))
+}
+
+/// How many bytes the elements of `shape` take up, which a corrupt or hostile shape could push
+/// past `usize`
+fn byte_count<T>(shape: &[usize], vm: &VirtualMachine) -> PyResult<usize> {
+    shape
+        .iter()
+        .try_fold(std::mem::size_of::<T>(), |bytes, &len| bytes.checked_mul(len))
+        .ok_or_else(|| vm.new_value_error(format!("Shape {shape:?} is too large")))
+}
+
+/// What the header says about the data that follows it
+#[derive(Clone, Debug)]
+pub struct NpyHeader {
+    pub descr: String,
+    pub fortran_order: bool,
+    pub shape: Vec<usize>,
+}
+
+/// Parses the header of a `.npy` file, giving it and the data after it
+pub fn read_npy_header<'a>(
+    bytes: &'a [u8],
+    vm: &VirtualMachine,
+) -> PyResult<(NpyHeader, &'a [u8])> {
+    let malformed = |what: &str| vm.new_value_error(format!("Malformed .npy file: {what}"));
+
+    let rest = bytes
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| malformed("no magic string"))?;
+    let (header_len, rest) = match rest {
+        [1, _, a, b, rest @ ..] => (usize::from(u16::from_le_bytes([*a, *b])), rest),
+        [2 | 3, _, a, b, c, d, rest @ ..] => (u32::from_le_bytes([*a, *b, *c, *d]) as usize, rest),
+        _ => return Err(malformed("unsupported version")),
+    };
+    let header = rest
+        .get(..header_len)
+        .and_then(|header| std::str::from_utf8(header).ok())
+        .ok_or_else(|| malformed("truncated header"))?;
+
+    let descr = dict_value(header, "descr")
+        .and_then(|descr| descr.strip_prefix('\'')?.strip_suffix('\''))
+        .ok_or_else(|| malformed("no descr"))?;
+    let fortran_order = match dict_value(header, "fortran_order") {
+        Some("True") => true,
+        Some("False") => false,
+        _ => return Err(malformed("no fortran_order")),
+    };
+    let shape = dict_value(header, "shape")
+        .and_then(|shape| shape.strip_prefix('(')?.strip_suffix(')'))
+        .ok_or_else(|| malformed("no shape"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|len| !len.is_empty())
+        .map(|len| len.parse().map_err(|_| malformed("bad shape")))
+        .collect::<PyResult<Vec<usize>>>()?;
+
+    let header = NpyHeader {
+        descr: descr.to_string(),
+        fortran_order,
+        shape,
+    };
+    Ok((header, &rest[header_len..]))
+}
+
+/// Reads the elements after the header into an array
+pub fn read_npy_data<T: NpyElement>(
+    header: &NpyHeader,
+    data: &[u8],
+    vm: &VirtualMachine,
+) -> PyResult<ArrayD<T>> {
+    let data = data
+        .get(..byte_count::<T>(&header.shape, vm)?)
+        .ok_or_else(|| vm.new_value_error("Malformed .npy file: truncated data".into()))?;
+
+    if header.fortran_order {
+        // Column-major data is the row-major layout of the transpose
+        let reversed = header.shape.iter().rev().copied().collect();
+        Ok(from_le_bytes(reversed, data, vm)?.reversed_axes())
+    } else {
+        from_le_bytes(header.shape.clone(), data, vm)
+    }
+}
+
+/// The text of the value for `key` in the header's dict literal
+fn dict_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{key}':"))? + key.len() + 3;
+    let rest = header[start..].trim_start();
+
+    // Tuples contain commas of their own, so they run to the closing parenthesis
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else {
+        rest.find([',', '}'])?
+    };
+    Some(rest[..end].trim())
+}
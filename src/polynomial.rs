@@ -0,0 +1,63 @@
+use num_traits::Float;
+
+/// Evaluates the polynomial with `coeffs` (highest degree first, as in numpy) at `x`, by
+/// Horner's method
+pub fn polyval<T: Float>(coeffs: &[T], x: T) -> T {
+    coeffs.iter().fold(T::zero(), |acc, &c| acc * x + c)
+}
+
+/// Least-squares fit of a degree `deg` polynomial to the points `(x, y)`, highest degree
+/// coefficient first. None when the fit is singular, e.g. with repeated `x`.
+pub fn polyfit(x: &[f64], y: &[f64], deg: usize) -> Option<Vec<f64>> {
+    let n = deg + 1;
+
+    // The normal equations VᵀV c = Vᵀy, with V the Vandermonde matrix of x, augmented with the
+    // right hand side as the last column
+    let mut system = vec![vec![0.0; n + 1]; n];
+    for (&xi, &yi) in x.iter().zip(y) {
+        let powers: Vec<f64> = (0..n).rev().map(|power| xi.powi(power as i32)).collect();
+        for (equation, &row_power) in system.iter_mut().zip(&powers) {
+            for (entry, &col_power) in equation.iter_mut().zip(&powers) {
+                *entry += row_power * col_power;
+            }
+            equation[n] += row_power * yi;
+        }
+    }
+
+    solve(system)
+}
+
+/// Solves an augmented system of linear equations by Gaussian elimination with partial pivoting
+fn solve(mut system: Vec<Vec<f64>>) -> Option<Vec<f64>> {
+    let n = system.len();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| system[i][col].abs().total_cmp(&system[j][col].abs()))?;
+        if system[pivot][col] == 0.0 {
+            return None;
+        }
+        system.swap(col, pivot);
+
+        let (done, rest) = system.split_at_mut(col + 1);
+        let pivot_row = &done[col];
+        for equation in rest {
+            let factor = equation[col] / pivot_row[col];
+            for (entry, &pivot_entry) in equation.iter_mut().zip(pivot_row).skip(col) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; n];
+    for (row, equation) in system.iter().enumerate().rev() {
+        let known: f64 = equation[row + 1..n]
+            .iter()
+            .zip(&solution[row + 1..])
+            .map(|(coeff, value)| coeff * value)
+            .sum();
+        solution[row] = (equation[n] - known) / equation[row];
+    }
+
+    Some(solution)
+}
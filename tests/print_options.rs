@@ -0,0 +1,60 @@
+//! Print options are shared by the whole process, so they're tested apart from the other tests,
+//! which would otherwise see them change while they run
+
+use rustpython_vm::{builtins::PyBaseExceptionRef, Interpreter, VirtualMachine};
+
+fn get_interpreter() -> Interpreter {
+    rustpython::InterpreterConfig::new()
+        .init_stdlib()
+        .init_hook(Box::new(|vm| {
+            vm.add_native_module(
+                "ndarray".to_owned(),
+                Box::new(rustpython_ndarray::make_module),
+            );
+        }))
+        .interpreter()
+}
+
+#[track_caller]
+fn run_code(source: &str) {
+    let interp = get_interpreter();
+    interp.enter(|vm| {
+        let scope = vm.new_scope_with_builtins();
+        let ndarray = vm.import("ndarray", 0).unwrap();
+        scope.globals.set_item("nd", ndarray, vm).unwrap();
+
+        vm.run_block_expr(scope, source)
+            .map_err(|e| write_exception(e, vm))
+            .unwrap();
+    })
+}
+
+fn write_exception(excp: PyBaseExceptionRef, vm: &VirtualMachine) -> String {
+    let mut s = String::new();
+    vm.write_exception(&mut s, &excp).unwrap();
+    s
+}
+
+#[test]
+fn set_printoptions() {
+    run_code(
+        r#"
+a = nd.array([1.0 / 3.0, 2.5, 4.0])
+assert str(a) == '[0.33333334, 2.5, 4.0]'
+
+nd.set_printoptions(precision=2)
+assert str(a) == '[0.33, 2.5, 4.0]'
+assert repr(a) == "array([0.33, 2.5, 4.0], dtype='float32')"
+assert str(nd.array([[-0.126]], dtype='float64')) == '[[-0.13]]'
+assert str(nd.array([1, 2], dtype='int32')) == '[1, 2]'
+
+nd.set_printoptions(threshold=5, edgeitems=1)
+assert str(nd.arange(0.0, 5.0)) == '[0.0, ..., 4.0]'
+assert str(nd.arange(0.0, 4.0)) == '[0.0, 1.0, 2.0, 3.0]'
+
+# None leaves an option as it was
+nd.set_printoptions(precision=None)
+assert str(a) == '[0.33, 2.5, 4.0]'
+"#,
+    );
+}